@@ -0,0 +1,49 @@
+//! Benchmarks for opening the font, loading pages, and looking up glyphs.
+//! Run with `cargo bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use unifont_bitmap::Unifont;
+
+/// Pages to load, chosen to cover a range of sizes and densities: Basic
+/// Latin, Cyrillic, Devanagari, Box Drawing, a page of CJK ideographs, a page
+/// of Hangul, emoji, and a sparse page near the end of the BMP.
+const PAGES: &[u32] = &[0x00, 0x04, 0x09, 0x25, 0x4E, 0xAC, 0x1F6, 0xFF];
+
+fn open(c: &mut Criterion) {
+    c.bench_function("open", |b| b.iter(|| black_box(Unifont::open())));
+}
+
+fn load_page(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_page");
+    for &page in PAGES {
+	group.bench_function(format!("{:03X}", page), |b| {
+	    b.iter_batched_ref(Unifont::open,
+			       |unifont| unifont.load_page(black_box(page)),
+			       BatchSize::SmallInput)
+	});
+    }
+    group.finish();
+}
+
+fn get_bitmap(c: &mut Criterion) {
+    let text = "The quick brown fox jumps over the lazy dog. \
+		Съешь же ещё этих мягких французских булок. 今日は良い天気です。";
+    let mut unifont = Unifont::open();
+    unifont.preload_str(text);
+    c.bench_function("get_bitmap", |b| b.iter(|| {
+	for ch in black_box(text).chars() {
+	    black_box(unifont.get_bitmap(ch as u32));
+	}
+    }));
+}
+
+fn preload_all(c: &mut Criterion) {
+    c.bench_function("preload_all", |b| {
+	b.iter_batched_ref(Unifont::open,
+			   |unifont| unifont.preload_all(),
+			   BatchSize::LargeInput)
+    });
+}
+
+criterion_group!(benches, open, load_page, get_bitmap, preload_all);
+criterion_main!(benches);
@@ -1,133 +1,136 @@
 use std::{
-    collections::{HashMap, HashSet},
-    convert::TryInto,
     ffi::OsString,
-    io::{BufRead, Write},
+    fs::File,
+    io::{BufReader, BufWriter},
 };
 
-use regex::Regex;
+use unifont_bitmap::{Codec, FontBuilder, MAX_UNICODE_PAGE};
 
-// These constants are also defined in our crate, but we can't use our crate
-// for them, because our crate needs us to be independently able to compile the
-// font from .hex files in the first place.
-/// The largest codepoint value that is, or ever will be, legal in Unicode.
-pub const MAX_UNICODE_CODEPOINT: u32 = 0x10FFFF;
-/// The number of legal codepoint values that exist in Unicode.
-pub const NUM_UNICODE_CODEPOINTS: u32 = MAX_UNICODE_CODEPOINT + 1;
-/// The largest number of a 256-codepoint "page" that exists in Unicode.
-pub const MAX_UNICODE_PAGE: u32 = NUM_UNICODE_PAGES-1;
-/// The number of 256-codepoint "pages" that exist in Unicode.
-pub const NUM_UNICODE_PAGES: u32 = NUM_UNICODE_CODEPOINTS >> 8;
+/// Parses a range of planes, like `0-2` or `1`.
+fn parse_planes(arg: &OsString) -> Option<(u32, u32)> {
+    let arg = arg.to_str()?;
+    let (first, last) = arg.split_once('-').unwrap_or((arg, arg));
+    let range = (first.parse().ok()?, last.parse().ok()?);
+    if range.0 > range.1 || range.1 > 16 { return None }
+    Some(range)
+}
 
-enum Bitmap {
-    Narrow([u8; 16]),
-    Wide([u8; 32]),
+/// Parses a comma-separated list of ranges of pages, in hexadecimal, like
+/// `00-05,4E-9F` or `1F6`.
+fn parse_pages(arg: &OsString) -> Option<Vec<(u32, u32)>> {
+    arg.to_str()?.split(',').map(|range| {
+	let (first, last) = range.split_once('-').unwrap_or((range, range));
+	let range = (u32::from_str_radix(first, 16).ok()?,
+		     u32::from_str_radix(last, 16).ok()?);
+	if range.0 > range.1 || range.1 > MAX_UNICODE_PAGE { return None }
+	Some(range)
+    }).collect()
 }
 
 fn main() -> std::io::Result<()> {
-    let args: Vec<OsString> = std::env::args_os().collect();
-    if args.len() != 2 {
-	eprintln!("Usage: cat ~/unifont/font/precompiled/unifont{{,_upper}}-\
-		   14.0.01.hex | {} output.dat", args[0].to_string_lossy());
-	std::process::exit(1);
-    }
-    let mut active_pages: HashSet<u32> = HashSet::with_capacity(NUM_UNICODE_PAGES as usize);
-    let mut bitmaps: HashMap<u32, Bitmap> = HashMap::with_capacity(MAX_UNICODE_CODEPOINT as usize + 1);
-    let hex_line_match = Regex::new("^([0-9A-F]{4,6}):([0-9A-F]{32}{1,2})\r?$")
-	.unwrap();
-    eprintln!("Reading bitmaps...");
-    let stdin = std::io::stdin();
-    let stdin = stdin.lock();
-    for line in stdin.lines() {
-	let line = line?;
-	let matched = match hex_line_match.captures(&line) {
-	    Some(x) => x,
-	    None => {
-		eprintln!("Unmatched line: {:?}", line);
-		continue;
-	    },
-	};
-	let codepoint = u32::from_str_radix(matched.get(1).unwrap().as_str(), 16).unwrap();
-	let bitmap: Vec<u8> = matched.get(2).unwrap().as_str().as_bytes().chunks(2).map(|x| u8::from_str_radix(&std::str::from_utf8(x).unwrap(), 16).unwrap()).collect();
-	let bitmap = match bitmap.len() {
-	    16 => Bitmap::Narrow(bitmap[..].try_into().unwrap()),
-	    32 => Bitmap::Wide(bitmap[..].try_into().unwrap()),
-	    _ => unreachable!(),
-	};
-	bitmaps.insert(codepoint, bitmap);
-	let page = codepoint >> 8;
-	active_pages.insert(page);
-    }
-    eprintln!("{bitmaps} bitmaps, taking up {bytes} bytes (uncompressed) in \
-	       {pages} pages.",
-	      pages = active_pages.len(), bitmaps = bitmaps.len(),
-	      bytes = bitmaps.iter().fold(0, |sum, bitmap| {
-		  sum + match bitmap.1 {
-		      Bitmap::Narrow(_) => 16, Bitmap::Wide(_) => 32
-		  }
-	      }));
-    let mut encoded_pages: Vec<(u32, Vec<u8>)>
-	= Vec::with_capacity(active_pages.len());
-    let mut sizes_buf = Vec::with_capacity(256 * 2);
-    let mut bytes_buf = Vec::with_capacity(256 * 32);
-    let mut uncompressed_sizes = [0u16; NUM_UNICODE_PAGES as usize];
-    let mut compressed_sizes = [0u16; NUM_UNICODE_PAGES as usize];
-    eprintln!("Compressing...");
-    for page in active_pages.iter() {
-	sizes_buf.clear();
-	bytes_buf.clear();
-	for codepoint in (page << 8) .. (page << 8) + 256 {
-	    // we represent the sizes in this weird form so they're more
-	    // compressible. post-loading, the sizes will be overwritten
-	    // in-place with offsets.
-	    match bitmaps.get(&codepoint) {
-		// 0x0101 = invalid char
-		None => { sizes_buf.push(0x01); sizes_buf.push(0x01); },
-		// 0x0000 = narrow char
-		Some(Bitmap::Narrow(bits)) => {
-		    sizes_buf.push(0x00); sizes_buf.push(0x00);
-		    bytes_buf.extend_from_slice(bits);
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    let mut builder = FontBuilder::new();
+    let mut planes = (0, 16);
+    let mut pages = None;
+    while args.len() >= 3 {
+	if args[1] == "--dedup" {
+	    builder.set_dedup(true);
+	    args.remove(1);
+	    continue
+	}
+	else if args[1] == "--version" {
+	    builder.set_version(&args[2].to_string_lossy());
+	}
+	else if args[1] == "--codec" && args[2] == "zlib" {
+	    builder.set_codec(Codec::Zlib);
+	}
+	else if args[1] == "--codec" && args[2] == "zstd" {
+	    #[cfg(feature = "zstd")]
+	    builder.set_codec(Codec::Zstd);
+	    #[cfg(not(feature = "zstd"))]
+	    {
+		eprintln!("--codec zstd needs compile-font to be built with \
+			   the zstd feature, e.g.\n\
+			   cargo run --release --features zstd -- ...");
+		std::process::exit(1);
+	    }
+	}
+	else if args[1] == "--planes" {
+	    match parse_planes(&args[2]) {
+		Some(range) => planes = range,
+		None => {
+		    eprintln!("Invalid plane range: {}",
+			      args[2].to_string_lossy());
+		    std::process::exit(1);
 		},
-		// 0x0001 = wide char
-		Some(Bitmap::Wide(bits)) => {
-		    sizes_buf.push(0x00); sizes_buf.push(0x01);
-		    bytes_buf.extend_from_slice(bits);
+	    }
+	}
+	else if args[1] == "--pages" {
+	    match parse_pages(&args[2]) {
+		Some(ranges) => pages = Some(ranges),
+		None => {
+		    eprintln!("Invalid page ranges: {}",
+			      args[2].to_string_lossy());
+		    std::process::exit(1);
 		},
 	    }
 	}
-	let uncompressed_length = sizes_buf.len() + bytes_buf.len();
-	assert!(uncompressed_length <= 32768);
-	let mut e = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
-	e.write_all(&sizes_buf[..]).unwrap();
-	e.write_all(&bytes_buf[..]).unwrap();
-	let compressed = e.finish().unwrap();
-	assert!(compressed.len() <= 65536);
-	uncompressed_sizes[*page as usize] = uncompressed_length as u16;
-	compressed_sizes[*page as usize] = compressed.len() as u16;
-	encoded_pages.push((*page, compressed));
+	else { break }
+	args.drain(1..3);
     }
-    let uncompressed_size = uncompressed_sizes.iter().fold(0, |tot, wat| {
-	tot + *wat as usize
-    });
-    let compressed_size = compressed_sizes.iter().fold(0, |tot, wat| {
-	tot + *wat as usize
-    });
-    eprintln!("Uncompressed size: {}", uncompressed_size);
-    eprintln!("  Compressed size: {}", compressed_size);
-    let ratio = uncompressed_size * 100 / compressed_size;
-    eprintln!("Compression ratio: 1 to {}.{:02}", ratio / 100, ratio % 100);
-    encoded_pages.sort();
-    let mut e = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
-    for page in 0 .. NUM_UNICODE_PAGES as usize {
-	e.write_all(&uncompressed_sizes[page].to_be_bytes()).unwrap();
-	e.write_all(&compressed_sizes[page].to_be_bytes()).unwrap();
+    if args.len() < 2 {
+	eprintln!("Usage: {0} [--version 14.0.01] [--codec zlib|zstd] \
+		   [--planes 0-16] [--pages 00-10FF,...] [--dedup] \
+		   output.dat input.hex...\n\
+		   \n\
+		   Input files are merged in order, so glyphs in later files \
+		   replace glyphs\nin earlier ones. With no input files, \
+		   reads from stdin instead, e.g.:\n\
+		   \n\
+		   cat ~/unifont/font/precompiled/unifont{{,_upper}}-\
+		   14.0.01.hex | {0} --version 14.0.01 output.dat\n\
+		   \n\
+		   --planes leaves out every glyph outside the given range of \
+		   planes. e.g.\n`--planes 0` keeps only the Basic \
+		   Multilingual Plane. --pages does the same for\n\
+		   comma-separated ranges of 256-codepoint pages, given in \
+		   hexadecimal. e.g.\n`--pages 00-05,4E-9F` keeps Latin, \
+		   Greek, Cyrillic, and the CJK Unified Ideographs.\n\
+		   U+FFFD REPLACEMENT CHARACTER is always kept.\n\
+		   \n\
+		   --dedup stores identical glyphs within a page only once. \
+		   This saves memory\nonce pages are loaded, but usually \
+		   makes the output file slightly bigger.",
+		  args[0].to_string_lossy());
+	std::process::exit(1);
     }
-    let compressed_page_table = e.finish().unwrap();
-    let mut output = std::fs::File::create(&args[1]).unwrap();
-    output.write_all(&(compressed_page_table.len() as u32).to_be_bytes()).unwrap();
-    output.write_all(&compressed_page_table).unwrap();
-    for (_, bytes) in encoded_pages.iter() {
-	output.write_all(bytes).unwrap();
+    if args.len() == 2 {
+	eprintln!("Reading bitmaps from stdin...");
+	builder.add_hex(std::io::stdin().lock())?;
+    }
+    for path in &args[2..] {
+	eprintln!("Reading bitmaps from {}...", path.to_string_lossy());
+	builder.add_hex(BufReader::new(File::open(path)?))?;
+    }
+    builder.retain(|codepoint| {
+	let page = codepoint >> 8;
+	codepoint == 0xFFFD
+	    || ((planes.0 ..= planes.1).contains(&(codepoint >> 16))
+		&& pages.as_ref().is_none_or(|pages: &Vec<(u32, u32)>| {
+		    pages.iter().any(|&(first, last)| (first ..= last)
+				     .contains(&page))
+		}))
+    });
+    eprintln!("Compressing...");
+    let output = BufWriter::new(File::create(&args[1])?);
+    let stats = builder.write_to(output)?;
+    if stats.skipped_lines > 0 {
+	eprintln!("Skipped {} unmatched lines.", stats.skipped_lines);
     }
+    eprintln!("{} bitmaps, taking up {} bytes (uncompressed) in {} pages.",
+	      stats.bitmaps, stats.bitmap_bytes, stats.pages);
+    eprintln!("Uncompressed size: {}", stats.uncompressed_size);
+    eprintln!("  Compressed size: {}", stats.compressed_size);
+    eprintln!("Compression ratio: 1 to {:.2}", stats.compression_ratio());
     Ok(())
 }
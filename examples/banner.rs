@@ -5,24 +5,20 @@ use unifont_bitmap::Unifont;
 
 fn banner_print(unifont: &mut Unifont, ink: char, wat: &str) {
     for c in wat.chars() {
-	let bitmap = unifont.load_bitmap(c as u32);
-	let pitch = if bitmap.is_wide() { 2 } else { 1 };
-	for x in 0..bitmap.get_dimensions().0 {
+	let bitmap = unifont.load_char(c);
+	for x in 0..bitmap.get_dimensions::<u32>().0 {
 	    for _ in 0 .. 2 {
 		for y in (0..16).rev() {
 		    for _ in 0 .. 2 {
-			let bi = (x/8) + y*pitch;
-			let shift = x%8;
-			let b = bitmap.get_bytes()[bi];
-			if (128 >> shift) & b == 0 {
-			    print!(" ");
+			if bitmap.get_pixel(x, y) {
+			    print!("{}", ink);
 			}
 			else {
-			    print!("{}", ink);
+			    print!(" ");
 			}
 		    }
 		}
-		println!("");
+		println!();
 	    }
 	}
     }
@@ -49,20 +45,20 @@ fn main() {
 	std::process::exit(1);
     }
     let mut args = &args[1..];
-    let ink = if args.get(0).map(String::as_str) == Some("--blocks") {
+    let ink = if args.first().map(String::as_str) == Some("--blocks") {
 	args = &args[1..];
 	'\u{2588}'
     } else { '#' };
     let mut unifont = Unifont::open();
-    if args.get(0).map(String::as_str) == Some("--") {
+    if args.first().map(String::as_str) == Some("--") {
 	args = &args[1..];
     }
-    if args.len() == 0 {
+    if args.is_empty() {
 	// read lines and print those as banner
 	let stdin = stdin();
-	let mut lines = stdin.lock().lines();
+	let lines = stdin.lock().lines();
 	let mut first = true;
-	while let Some(line) = lines.next() {
+	for line in lines {
 	    let line = line.unwrap();
 	    if !first {
 		banner_print(&mut unifont, ink, " ");
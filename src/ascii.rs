@@ -0,0 +1,227 @@
+//! Glyphs for printable ASCII and U+FFFD REPLACEMENT CHARACTER, as plain
+//! tables. These can be used without any decompression or allocation, even
+//! without a `Unifont` instance.
+//!
+//! These tables were generated from GNU Unifont 14.0.01, and must match the
+//! embedded font data.
+
+/// The glyph for U+FFFD REPLACEMENT CHARACTER, which is narrow. This is the
+/// glyph that `Unifont::load_bitmap` returns for codepoints with no glyph,
+/// even if the font data doesn't have a U+FFFD of its own.
+pub const REPLACEMENT_BITMAP: [u8; 16] =
+    [0x00, 0x00, 0x00, 0x7E, 0x66, 0x5A, 0x5A, 0x7A,
+     0x76, 0x76, 0x7E, 0x76, 0x76, 0x7E, 0x00, 0x00];
+
+/// The glyphs for the printable ASCII characters, U+0020 SPACE through U+007E
+/// TILDE, in order. They're all narrow. `ASCII_BITMAPS[0]` is the glyph for
+/// space.
+pub const ASCII_BITMAPS: [[u8; 16]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // space
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x08, 0x08, // !
+     0x08, 0x08, 0x08, 0x00, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x22, 0x22, 0x22, 0x22, 0x00, 0x00, // "
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x12, 0x12, 0x12, 0x7E, // #
+     0x24, 0x24, 0x7E, 0x48, 0x48, 0x48, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x3E, 0x49, 0x48, // $
+     0x38, 0x0E, 0x09, 0x49, 0x3E, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x31, 0x4A, 0x4A, 0x34, // %
+     0x08, 0x08, 0x16, 0x29, 0x29, 0x46, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x1C, 0x22, 0x22, 0x14, // &
+     0x18, 0x29, 0x45, 0x42, 0x46, 0x39, 0x00, 0x00],
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, // '
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x04, 0x08, 0x08, 0x10, 0x10, // (
+     0x10, 0x10, 0x10, 0x10, 0x08, 0x08, 0x04, 0x00],
+    [0x00, 0x00, 0x00, 0x20, 0x10, 0x10, 0x08, 0x08, // )
+     0x08, 0x08, 0x08, 0x08, 0x10, 0x10, 0x20, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x49, // *
+     0x2A, 0x1C, 0x2A, 0x49, 0x08, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x08, // +
+     0x08, 0x7F, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ,
+     0x00, 0x00, 0x00, 0x00, 0x18, 0x08, 0x08, 0x10],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // -
+     0x00, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // .
+     0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x02, 0x02, 0x04, 0x08, // /
+     0x08, 0x10, 0x10, 0x20, 0x40, 0x40, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x18, 0x24, 0x42, 0x46, // 0
+     0x4A, 0x52, 0x62, 0x42, 0x24, 0x18, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x18, 0x28, 0x08, // 1
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x3E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x02, // 2
+     0x0C, 0x10, 0x20, 0x40, 0x40, 0x7E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x02, // 3
+     0x1C, 0x02, 0x02, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x04, 0x0C, 0x14, 0x24, // 4
+     0x44, 0x44, 0x7E, 0x04, 0x04, 0x04, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7E, 0x40, 0x40, 0x40, // 5
+     0x7C, 0x02, 0x02, 0x02, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x1C, 0x20, 0x40, 0x40, // 6
+     0x7C, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7E, 0x02, 0x02, 0x04, // 7
+     0x04, 0x04, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, // 8
+     0x3C, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, // 9
+     0x3E, 0x02, 0x02, 0x02, 0x04, 0x38, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, // :
+     0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, // ;
+     0x00, 0x00, 0x00, 0x18, 0x08, 0x08, 0x10, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x04, 0x08, // <
+     0x10, 0x20, 0x10, 0x08, 0x04, 0x02, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7E, // =
+     0x00, 0x00, 0x00, 0x7E, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x20, 0x10, // >
+     0x08, 0x04, 0x08, 0x10, 0x20, 0x40, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x02, // ?
+     0x04, 0x08, 0x08, 0x00, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x1C, 0x22, 0x4A, 0x56, // @
+     0x52, 0x52, 0x52, 0x4E, 0x20, 0x1E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x18, 0x24, 0x24, 0x42, // A
+     0x42, 0x7E, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7C, 0x42, 0x42, 0x42, // B
+     0x7C, 0x42, 0x42, 0x42, 0x42, 0x7C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x40, // C
+     0x40, 0x40, 0x40, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x78, 0x44, 0x42, 0x42, // D
+     0x42, 0x42, 0x42, 0x42, 0x44, 0x78, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7E, 0x40, 0x40, 0x40, // E
+     0x7C, 0x40, 0x40, 0x40, 0x40, 0x7E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7E, 0x40, 0x40, 0x40, // F
+     0x7C, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x40, // G
+     0x40, 0x4E, 0x42, 0x42, 0x46, 0x3A, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x42, 0x42, // H
+     0x7E, 0x42, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3E, 0x08, 0x08, 0x08, // I
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x3E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x1F, 0x04, 0x04, 0x04, // J
+     0x04, 0x04, 0x04, 0x44, 0x44, 0x38, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x44, 0x48, 0x50, // K
+     0x60, 0x60, 0x50, 0x48, 0x44, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x40, 0x40, 0x40, 0x40, // L
+     0x40, 0x40, 0x40, 0x40, 0x40, 0x7E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x66, 0x66, // M
+     0x5A, 0x5A, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x62, 0x62, 0x52, // N
+     0x52, 0x4A, 0x4A, 0x46, 0x46, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, // O
+     0x42, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7C, 0x42, 0x42, 0x42, // P
+     0x7C, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x42, // Q
+     0x42, 0x42, 0x42, 0x5A, 0x66, 0x3C, 0x03, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7C, 0x42, 0x42, 0x42, // R
+     0x7C, 0x48, 0x44, 0x44, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x40, // S
+     0x30, 0x0C, 0x02, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7F, 0x08, 0x08, 0x08, // T
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x42, 0x42, // U
+     0x42, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x41, 0x41, 0x41, 0x22, // V
+     0x22, 0x22, 0x14, 0x14, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x42, 0x42, // W
+     0x5A, 0x5A, 0x66, 0x66, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x42, 0x24, 0x24, // X
+     0x18, 0x18, 0x24, 0x24, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x41, 0x41, 0x22, 0x22, // Y
+     0x14, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x7E, 0x02, 0x02, 0x04, // Z
+     0x08, 0x10, 0x20, 0x40, 0x40, 0x7E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x0E, 0x08, 0x08, 0x08, 0x08, // [
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x40, 0x40, 0x20, 0x10, // backslash
+     0x10, 0x08, 0x08, 0x04, 0x02, 0x02, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x70, 0x10, 0x10, 0x10, 0x10, // ]
+     0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x70, 0x00],
+    [0x00, 0x00, 0x18, 0x24, 0x42, 0x00, 0x00, 0x00, // ^
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // _
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x00],
+    [0x00, 0x20, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, // `
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, // a
+     0x02, 0x3E, 0x42, 0x42, 0x46, 0x3A, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x40, 0x40, 0x40, 0x5C, 0x62, // b
+     0x42, 0x42, 0x42, 0x42, 0x62, 0x5C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, // c
+     0x40, 0x40, 0x40, 0x40, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x02, 0x02, 0x02, 0x3A, 0x46, // d
+     0x42, 0x42, 0x42, 0x42, 0x46, 0x3A, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, // e
+     0x42, 0x7E, 0x40, 0x40, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x0C, 0x10, 0x10, 0x10, 0x7C, // f
+     0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x3A, 0x44, // g
+     0x44, 0x44, 0x38, 0x20, 0x3C, 0x42, 0x42, 0x3C],
+    [0x00, 0x00, 0x00, 0x40, 0x40, 0x40, 0x5C, 0x62, // h
+     0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x08, 0x08, 0x00, 0x18, 0x08, // i
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x3E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x04, 0x04, 0x00, 0x0C, 0x04, // j
+     0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x48, 0x30],
+    [0x00, 0x00, 0x00, 0x40, 0x40, 0x40, 0x44, 0x48, // k
+     0x50, 0x60, 0x50, 0x48, 0x44, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x18, 0x08, 0x08, 0x08, 0x08, // l
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x3E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x76, 0x49, // m
+     0x49, 0x49, 0x49, 0x49, 0x49, 0x49, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5C, 0x62, // n
+     0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, // o
+     0x42, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5C, 0x62, // p
+     0x42, 0x42, 0x42, 0x42, 0x62, 0x5C, 0x40, 0x40],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x46, // q
+     0x42, 0x42, 0x42, 0x42, 0x46, 0x3A, 0x02, 0x02],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5C, 0x62, // r
+     0x42, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3C, 0x42, // s
+     0x40, 0x30, 0x0C, 0x02, 0x42, 0x3C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x10, 0x10, 0x10, 0x7C, // t
+     0x10, 0x10, 0x10, 0x10, 0x10, 0x0C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, // u
+     0x42, 0x42, 0x42, 0x42, 0x46, 0x3A, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, // v
+     0x42, 0x24, 0x24, 0x24, 0x18, 0x18, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x41, 0x49, // w
+     0x49, 0x49, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, // x
+     0x24, 0x18, 0x18, 0x24, 0x42, 0x42, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x42, 0x42, // y
+     0x42, 0x42, 0x42, 0x26, 0x1A, 0x02, 0x02, 0x3C],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7E, 0x02, // z
+     0x04, 0x08, 0x10, 0x20, 0x40, 0x7E, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x0C, 0x10, 0x10, 0x08, 0x08, // {
+     0x10, 0x20, 0x10, 0x08, 0x08, 0x10, 0x10, 0x0C],
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, // |
+     0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08],
+    [0x00, 0x00, 0x00, 0x30, 0x08, 0x08, 0x10, 0x10, // }
+     0x08, 0x04, 0x08, 0x10, 0x10, 0x08, 0x08, 0x30],
+    [0x00, 0x00, 0x00, 0x31, 0x49, 0x46, 0x00, 0x00, // ~
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+static ASCII_TABLE: [[u8; 16]; 95] = ASCII_BITMAPS;
+pub(crate) static REPLACEMENT_TABLE: [u8; 16] = REPLACEMENT_BITMAP;
+
+/// Returns the bytes of the glyph for the given ASCII character, in the same
+/// format as `Bitmap::get_bytes`. The glyph is always narrow (8x16), so there
+/// are always 16 bytes. This never decompresses or allocates anything, and
+/// doesn't need the `embed-data` feature.
+///
+/// Only the printable characters, `0x20` (space) through `0x7E` (`~`), are
+/// covered. For any other byte, including control characters (whose Unifont
+/// glyphs are wide), this returns the glyph for U+FFFD REPLACEMENT CHARACTER.
+pub fn ascii_bitmap(c: u8) -> &'static [u8] {
+    match c {
+	0x20 ..= 0x7E => &ASCII_TABLE[(c - 0x20) as usize],
+	_ => &REPLACEMENT_TABLE,
+    }
+}
@@ -0,0 +1,77 @@
+//! Packing glyphs into a single texture, for GPU rendering.
+
+use std::collections::HashMap;
+
+use crate::{BitmapBuf, Unifont};
+
+/// A set of glyphs packed into a single 8-bit grayscale image, made by
+/// `Unifont::build_atlas`. Glyphs are packed into shelves 16 pixels tall,
+/// left to right, top to bottom.
+#[derive(Debug,Clone)]
+pub struct Atlas {
+    /// Width of the image, in pixels. Always a multiple of 16.
+    pub width: u32,
+    /// Height of the image, in pixels. Always a multiple of 16.
+    pub height: u32,
+    /// The image, one byte per pixel, row by row. Inked pixels are `0xFF`,
+    /// all other pixels are `0x00`.
+    pub pixels: Vec<u8>,
+    /// Where each codepoint's glyph is in the image, as `(x, y, width)`. The
+    /// glyph is always 16 pixels tall.
+    pub glyphs: HashMap<u32, (u32, u32, u32)>,
+}
+
+impl Atlas {
+    /// Returns where the given codepoint's glyph is in the image, as
+    /// `(x, y, width)`, or `None` if it wasn't one of the codepoints the atlas
+    /// was built with.
+    pub fn get(&self, codepoint: u32) -> Option<(u32, u32, u32)> {
+	self.glyphs.get(&codepoint).copied()
+    }
+}
+
+impl Unifont {
+    /// Packs the glyphs for the given codepoints into a single grayscale
+    /// image. As with `load_bitmap`, codepoints with no glyph get U+FFFD
+    /// REPLACEMENT CHAR. Identical glyphs are only packed once, and share
+    /// coordinates. The image is made roughly square.
+    ///
+    /// **PANICS** if any of the codepoints are larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn build_atlas(&mut self, codepoints: &[u32]) -> Atlas {
+	let mut unique: Vec<BitmapBuf> = Vec::new();
+	let mut indices: HashMap<BitmapBuf, usize> = HashMap::new();
+	let mut glyph_indices = Vec::with_capacity(codepoints.len());
+	for &codepoint in codepoints {
+	    let bitmap = self.load_bitmap(codepoint).to_owned();
+	    let index = *indices.entry(bitmap).or_insert_with(|| {
+		unique.push(bitmap);
+		unique.len() - 1
+	    });
+	    glyph_indices.push(index);
+	}
+	// Pick a width that makes the atlas roughly square, then fill shelves.
+	let area: u32 = unique.iter().map(|x| x.width() * 16).sum();
+	let width = (((area as f64).sqrt() / 16.0).ceil() as u32).max(1) * 16;
+	let mut positions = Vec::with_capacity(unique.len());
+	let (mut x, mut y) = (0, 0);
+	for bitmap in unique.iter() {
+	    if x + bitmap.width() > width {
+		x = 0;
+		y += 16;
+	    }
+	    positions.push((x, y, bitmap.width()));
+	    x += bitmap.width();
+	}
+	let height = if unique.is_empty() { 0 } else { y + 16 };
+	let mut pixels = vec![0; (width * height) as usize];
+	for (bitmap, &(x, y, _)) in unique.iter().zip(positions.iter()) {
+	    bitmap.as_bitmap().blit_alpha(&mut pixels, width as usize,
+					  x as usize, y as usize);
+	}
+	let glyphs = codepoints.iter().zip(glyph_indices)
+	    .map(|(&codepoint, index)| (codepoint, positions[index]))
+	    .collect();
+	Atlas { width, height, pixels, glyphs }
+    }
+}
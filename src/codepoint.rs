@@ -0,0 +1,63 @@
+//! A codepoint that is known to be in range.
+
+use crate::MAX_UNICODE_CODEPOINT;
+
+/// A codepoint that is no larger than `MAX_UNICODE_CODEPOINT`. Methods that
+/// take one instead of a raw `u32` don't have to check it, so they can't panic
+/// because of it.
+///
+/// Make one from a `char` with `From`, or from a `u32` with `TryFrom`, which
+/// fails if it's out of range. Surrogates (U+D800 through U+DFFF) are
+/// allowed, since they're in range, but they never have glyphs; check for
+/// them with `is_surrogate` if that matters to you.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct CodePoint(u32);
+
+impl CodePoint {
+    /// Returns the codepoint as a `u32`.
+    pub fn get(self) -> u32 { self.0 }
+    /// Returns the number of the 256-codepoint page that this codepoint is in.
+    pub fn page(self) -> u32 { self.0 >> 8 }
+    /// Returns `true` if this is a surrogate (U+D800 through U+DFFF), which
+    /// can't appear in a `char`, and never has a glyph.
+    pub fn is_surrogate(self) -> bool { crate::is_surrogate(self.0) }
+}
+
+impl From<char> for CodePoint {
+    fn from(c: char) -> CodePoint { CodePoint(c as u32) }
+}
+
+impl From<CodePoint> for u32 {
+    fn from(codepoint: CodePoint) -> u32 { codepoint.0 }
+}
+
+impl TryFrom<u32> for CodePoint {
+    type Error = InvalidCodePoint;
+    fn try_from(codepoint: u32) -> Result<CodePoint, InvalidCodePoint> {
+	if codepoint <= MAX_UNICODE_CODEPOINT { Ok(CodePoint(codepoint)) }
+	else { Err(InvalidCodePoint(codepoint)) }
+    }
+}
+
+/// Displays as `U+` followed by at least four hexadecimal digits, e.g.
+/// `U+00FF`.
+impl core::fmt::Display for CodePoint {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+	write!(fmt, "U+{:04X}", self.0)
+    }
+}
+
+/// The error returned when trying to make a `CodePoint` out of a `u32` that
+/// is larger than `MAX_UNICODE_CODEPOINT`. Contains the offending value.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct InvalidCodePoint(pub u32);
+
+impl core::fmt::Display for InvalidCodePoint {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+	write!(fmt, "{:#X} is larger than the largest Unicode codepoint",
+	       self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCodePoint {}
@@ -0,0 +1,292 @@
+//! Compiling `.hex` files into the `.dat` format that `Unifont::from_bytes`
+//! and friends read. This is the logic behind the `compile-font` tool.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, BufRead, Write},
+};
+
+use crate::{
+    BitmapBuf, MAX_UNICODE_CODEPOINT, NUM_UNICODE_PAGES,
+    header::{Codec, FontHeader},
+};
+
+/// Statistics about a font compiled by `compile_from_hex`.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct CompileStats {
+    /// How many glyphs the font contains.
+    pub bitmaps: usize,
+    /// How many bytes the glyphs take up, uncompressed.
+    pub bitmap_bytes: usize,
+    /// How many 256-codepoint pages contain at least one glyph.
+    pub pages: usize,
+    /// Total size of all pages, uncompressed, including their glyph size
+    /// markers.
+    pub uncompressed_size: usize,
+    /// Total size of all pages, compressed. Doesn't include the page table.
+    pub compressed_size: usize,
+    /// How many lines of input weren't valid `.hex` lines, and were skipped.
+    pub skipped_lines: usize,
+}
+
+impl CompileStats {
+    /// Returns how many times smaller the compressed pages are than the
+    /// uncompressed ones.
+    pub fn compression_ratio(&self) -> f64 {
+	self.uncompressed_size as f64 / self.compressed_size as f64
+    }
+}
+
+/// Assembles font data from glyphs supplied one at a time, producing the
+/// same format as `compile_from_hex`. Use this to synthesize a font (e.g.
+/// icons, or private-use glyphs) in memory, then load it with
+/// `Unifont::from_bytes`.
+///
+/// Missing glyphs are drawn as the font's U+FFFD REPLACEMENT CHAR. If you
+/// don't add one, `REPLACEMENT_BITMAP` is used instead.
+#[derive(Debug,Clone,Default)]
+pub struct FontBuilder {
+    bitmaps: BTreeMap<u32, BitmapBuf>,
+    skipped_lines: usize,
+    header: FontHeader,
+    dedup: bool,
+}
+
+impl FontBuilder {
+    /// Creates a new builder, with no glyphs.
+    pub fn new() -> FontBuilder { FontBuilder::default() }
+    /// Adds a narrow (8x16) glyph for the given codepoint, replacing any
+    /// glyph that was already added for it. The bytes are in the same format
+    /// as `Bitmap::get_bytes`.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn add_narrow(&mut self, codepoint: u32, bytes: [u8; 16])
+		      -> &mut FontBuilder {
+	self.add(codepoint, &bytes)
+    }
+    /// Adds a wide (16x16) glyph for the given codepoint, replacing any glyph
+    /// that was already added for it. The bytes are in the same format as
+    /// `Bitmap::get_bytes`.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn add_wide(&mut self, codepoint: u32, bytes: [u8; 32])
+		    -> &mut FontBuilder {
+	self.add(codepoint, &bytes)
+    }
+    fn add(&mut self, codepoint: u32, bytes: &[u8]) -> &mut FontBuilder {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	self.bitmaps.insert(codepoint, BitmapBuf::from_bytes(bytes).unwrap());
+	self
+    }
+    /// Adds every glyph from the given `.hex` file (see `compile_from_hex`),
+    /// replacing any glyphs that were already added for the same codepoints.
+    /// Returns how many lines were skipped because they weren't valid `.hex`
+    /// lines.
+    pub fn add_hex<R: BufRead>(&mut self, input: R) -> io::Result<usize> {
+	let mut skipped_lines = 0;
+	for line in input.lines() {
+	    match parse_hex_line(&line?) {
+		Some((codepoint, bitmap))
+		    => { self.bitmaps.insert(codepoint, bitmap); },
+		None => skipped_lines += 1,
+	    }
+	}
+	self.skipped_lines += skipped_lines;
+	Ok(skipped_lines)
+    }
+    /// Removes every glyph whose codepoint `keep` returns `false` for, e.g. to
+    /// leave out scripts that you don't need.
+    pub fn retain<F: FnMut(u32) -> bool>(&mut self, mut keep: F)
+					 -> &mut FontBuilder {
+	self.bitmaps.retain(|&codepoint, _| keep(codepoint));
+	self
+    }
+    /// Sets the font version to record in the compiled data, which
+    /// `Unifont::font_version` will return.
+    pub fn set_version(&mut self, version: &str) -> &mut FontBuilder {
+	self.header.version = Some(version.to_owned());
+	self
+    }
+    /// Sets the compression format to use. The default is `Codec::Zlib`.
+    pub fn set_codec(&mut self, codec: Codec) -> &mut FontBuilder {
+	self.header.codec = codec;
+	self
+    }
+    /// Sets whether to store identical glyphs within the same page only
+    /// once. This shrinks the decompressed pages, and so the memory a loaded
+    /// page takes up, but usually makes the compressed data slightly
+    /// *bigger*, since compression already squeezes out most of the
+    /// repetition. (For the full font, it saves 6144 bytes of decompressed
+    /// pages and costs about 300 bytes of compressed data.) The default is
+    /// not to.
+    pub fn set_dedup(&mut self, dedup: bool) -> &mut FontBuilder {
+	self.dedup = dedup;
+	self
+    }
+    /// Returns the number of glyphs added so far.
+    pub fn len(&self) -> usize { self.bitmaps.len() }
+    /// Returns `true` if no glyphs have been added yet.
+    pub fn is_empty(&self) -> bool { self.bitmaps.is_empty() }
+    /// Compresses all of the glyphs, and returns the finished font data.
+    pub fn finish(&self) -> Vec<u8> {
+	let mut ret = Vec::new();
+	self.write_to(&mut ret).expect("writing to a Vec should never fail");
+	ret
+    }
+    /// Compresses all of the glyphs, and writes the finished font data to
+    /// `output`. `CompileStats::skipped_lines` counts every line skipped by
+    /// `add_hex` on this builder.
+    pub fn write_to<W: Write>(&self, output: W) -> io::Result<CompileStats> {
+	let header = FontHeader {
+	    glyph_count: Some(self.bitmaps.len() as u32),
+	    ..self.header.clone()
+	};
+	let mut stats = write_font(&header, &self.bitmaps, self.dedup,
+				   output)?;
+	stats.skipped_lines = self.skipped_lines;
+	Ok(stats)
+    }
+}
+
+/// Reads glyphs in GNU Unifont's `.hex` format from `input`, and writes a
+/// compiled `.dat` file to `output`.
+///
+/// Each line of a `.hex` file is a codepoint (4 to 6 hexadecimal digits), a
+/// colon, and the bitmap (32 hexadecimal digits for a narrow glyph, 64 for a
+/// wide one). Lines that aren't in this format are skipped, and counted in
+/// `CompileStats::skipped_lines`. If a codepoint appears more than once, the
+/// last one wins.
+pub fn compile_from_hex<R: BufRead, W: Write>(input: R, output: W)
+					      -> io::Result<CompileStats> {
+    let mut builder = FontBuilder::new();
+    builder.add_hex(input)?;
+    builder.write_to(output)
+}
+
+/// Parses a single line of a `.hex` file.
+fn parse_hex_line(line: &str) -> Option<(u32, BitmapBuf)> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let (codepoint, hex) = line.split_once(':')?;
+    if !(4..=6).contains(&codepoint.len())
+	|| !codepoint.bytes().all(|x| x.is_ascii_hexdigit()) {
+	return None
+    }
+    let codepoint = u32::from_str_radix(codepoint, 16).ok()?;
+    if codepoint > MAX_UNICODE_CODEPOINT
+	|| (hex.len() != 32 && hex.len() != 64)
+	|| !hex.bytes().all(|x| x.is_ascii_hexdigit()) {
+	return None
+    }
+    let bytes: Vec<u8> = (0 .. hex.len()).step_by(2)
+	.map(|n| u8::from_str_radix(&hex[n .. n + 2], 16).unwrap())
+	.collect();
+    Some((codepoint, BitmapBuf::from_bytes(&bytes)?))
+}
+
+/// Compresses the given glyphs, and writes them in `.dat` format.
+fn write_font<W: Write>(header: &FontHeader,
+			bitmaps: &BTreeMap<u32, BitmapBuf>, dedup: bool,
+			mut output: W) -> io::Result<CompileStats> {
+    let mut stats = CompileStats {
+	bitmaps: bitmaps.len(),
+	bitmap_bytes: bitmaps.values().map(|x| x.get_bytes().len()).sum(),
+	pages: 0,
+	uncompressed_size: 0,
+	compressed_size: 0,
+	skipped_lines: 0,
+    };
+    let mut encoded_pages: Vec<Vec<u8>> = Vec::new();
+    let mut sizes_buf = Vec::with_capacity(256 * 2 + 256 * 32);
+    let mut bytes_buf = Vec::with_capacity(256 * 32);
+    let mut seen: HashMap<&[u8], u8> = HashMap::with_capacity(256);
+    let mut uncompressed_sizes = [0u16; NUM_UNICODE_PAGES as usize];
+    let mut compressed_sizes = [0u16; NUM_UNICODE_PAGES as usize];
+    let mut next = bitmaps.keys().next().copied();
+    while let Some(first) = next {
+	let page = first >> 8;
+	sizes_buf.clear();
+	bytes_buf.clear();
+	seen.clear();
+	for codepoint in (page << 8) .. (page << 8) + 256 {
+	    // we represent the sizes in this weird form so they're more
+	    // compressible. post-loading, the sizes will be overwritten
+	    // in-place with offsets.
+	    match bitmaps.get(&codepoint) {
+		// 0x0101 = invalid char
+		None => sizes_buf.extend_from_slice(&[0x01, 0x01]),
+		Some(bitmap) => match seen.get(bitmap.get_bytes())
+		    .filter(|_| dedup) {
+		    // 0x02NN = same bitmap as char NN of this page
+		    Some(&earlier) => sizes_buf.extend_from_slice(&[0x02, earlier]),
+		    None => {
+			// 0x0000 = narrow char, 0x0001 = wide char
+			sizes_buf.extend_from_slice(&[0x00, bitmap.is_wide() as u8]);
+			bytes_buf.extend_from_slice(bitmap.get_bytes());
+			seen.insert(bitmap.get_bytes(), codepoint as u8);
+		    },
+		},
+	    }
+	}
+	// A page is at most 512 + 256 * 32 = 8704 bytes uncompressed, so this
+	// always fits in the page table's u16s.
+	let uncompressed_length = sizes_buf.len() + bytes_buf.len();
+	sizes_buf.extend_from_slice(&bytes_buf);
+	let compressed = compress(header.codec, &sizes_buf)?;
+	// The compressed size has to fit too. Compression can expand
+	// incompressible data a little, but nowhere near this much, so this
+	// should never fail. If it somehow does, say so instead of writing a
+	// broken file.
+	let compressed_length = u16::try_from(compressed.len()).map_err(|_| {
+	    io::Error::new(io::ErrorKind::InvalidData,
+			   format!("page {:X} compressed to {} bytes, more than \
+				    the page table can hold",
+				   page, compressed.len()))
+	})?;
+	uncompressed_sizes[page as usize] = uncompressed_length as u16;
+	compressed_sizes[page as usize] = compressed_length;
+	stats.pages += 1;
+	stats.uncompressed_size += uncompressed_length;
+	stats.compressed_size += compressed.len();
+	encoded_pages.push(compressed);
+	next = bitmaps.range((page + 1) << 8 ..).next().map(|(&x, _)| x);
+    }
+    let mut page_table = Vec::with_capacity(NUM_UNICODE_PAGES as usize * 4);
+    for page in 0 .. NUM_UNICODE_PAGES as usize {
+	page_table.extend_from_slice(&uncompressed_sizes[page].to_be_bytes());
+	page_table.extend_from_slice(&compressed_sizes[page].to_be_bytes());
+    }
+    let compressed_page_table = compress(header.codec, &page_table)?;
+    let table_length = (compressed_page_table.len() as u32).to_be_bytes();
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&table_length);
+    hasher.update(&compressed_page_table);
+    for bytes in encoded_pages.iter() {
+	hasher.update(bytes);
+    }
+    let header = FontHeader {
+	checksum: Some(hasher.finalize()),
+	..header.clone()
+    };
+    output.write_all(&header.encode())?;
+    output.write_all(&table_length)?;
+    output.write_all(&compressed_page_table)?;
+    for bytes in encoded_pages.iter() {
+	output.write_all(bytes)?;
+    }
+    Ok(stats)
+}
+
+/// Compresses `data` as tightly as possible with the given codec.
+fn compress(codec: Codec, data: &[u8]) -> io::Result<Vec<u8>> {
+    match codec {
+	Codec::Zlib => {
+	    let mut e = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+	    e.write_all(data)?;
+	    e.finish()
+	},
+	#[cfg(feature = "zstd")]
+	Codec::Zstd => zstd::bulk::compress(data, zstd::zstd_safe::max_c_level()),
+    }
+}
@@ -0,0 +1,142 @@
+//! The optional header at the start of a `.dat` file.
+//!
+//! A `.dat` file that starts with `HEADER_MAGIC` has a header: the magic, a
+//! big-endian `u32` giving the length of the rest of the header, and then
+//! that many bytes of records. Each record is a four-byte tag, a big-endian
+//! `u32` length, and that many bytes of payload. Records with unknown tags are
+//! skipped, so new ones can be added without breaking old readers. The page
+//! table follows the header. Files without a header start directly with the
+//! page table's length, which would have to be over a gigabyte to be mistaken
+//! for `HEADER_MAGIC`.
+
+use alloc::{borrow::ToOwned, string::String};
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::UnifontError;
+
+/// The bytes that a `.dat` file with a header starts with.
+pub(crate) const HEADER_MAGIC: &[u8; 4] = b"UFBM";
+/// Tag of the record holding the font version, as a UTF-8 string.
+const VERSION_TAG: &[u8; 4] = b"VERS";
+/// Tag of the record holding the number of glyphs, as a big-endian `u32`.
+const GLYPH_COUNT_TAG: &[u8; 4] = b"GCNT";
+/// Tag of the record holding the compression format, as a single byte. Files
+/// without one use zlib.
+const CODEC_TAG: &[u8; 4] = b"CODC";
+/// Tag of the record holding the CRC-32 of everything after the header, as a
+/// big-endian `u32`.
+const CHECKSUM_TAG: &[u8; 4] = b"CRC3";
+
+/// A compression format that font data can use, for both the page table and
+/// the pages themselves.
+///
+/// More formats may be added in the future, and which ones exist depends on
+/// which features are enabled, so matches on this need a wildcard arm.
+#[derive(Debug,Clone,Copy,Default,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum Codec {
+    /// zlib. This is the default, is always supported, and is what the
+    /// embedded font data uses.
+    #[default]
+    Zlib,
+    /// [Zstandard][1]. Compresses this data slightly better than zlib, and
+    /// decompresses it faster. Reading or writing font data that uses it
+    /// requires the `zstd` feature.
+    ///
+    /// [1]: https://facebook.github.io/zstd/
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+/// The information stored in a `.dat` file's header.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub(crate) struct FontHeader {
+    /// The version of the font that the file was compiled from, if known.
+    pub version: Option<String>,
+    /// The number of glyphs in the file, if known.
+    pub glyph_count: Option<u32>,
+    /// How the page table and pages are compressed.
+    pub codec: Codec,
+    /// The CRC-32 of everything after the header, if known.
+    pub checksum: Option<u32>,
+}
+
+impl FontHeader {
+    /// Parses the header at the start of `data`, if there is one. Returns the
+    /// header (empty if there wasn't one) and the offset of the page table.
+    pub fn parse(data: &[u8]) -> Result<(FontHeader, usize), UnifontError> {
+	let mut header = FontHeader::default();
+	if !data.starts_with(HEADER_MAGIC) {
+	    return Ok((header, 0))
+	}
+	let length = data.get(4..8).map(BigEndian::read_u32)
+	    .ok_or(UnifontError::Truncated)? as usize;
+	let mut records = data.get(8..).and_then(|x| x.get(..length))
+	    .ok_or(UnifontError::Truncated)?;
+	while !records.is_empty() {
+	    let (tag, record_length) = records.get(..8)
+		.map(|x| (&x[..4], BigEndian::read_u32(&x[4..]) as usize))
+		.ok_or(UnifontError::MalformedHeader)?;
+	    records = &records[8..];
+	    let payload = records.get(..record_length)
+		.ok_or(UnifontError::MalformedHeader)?;
+	    records = &records[record_length..];
+	    if tag == VERSION_TAG {
+		header.version = Some(core::str::from_utf8(payload)
+				      .map_err(|_| UnifontError::MalformedHeader)?
+				      .to_owned());
+	    }
+	    else if tag == GLYPH_COUNT_TAG {
+		header.glyph_count = Some(u32::from_be_bytes(payload.try_into()
+				   .map_err(|_| UnifontError::MalformedHeader)?));
+	    }
+	    else if tag == CODEC_TAG {
+		header.codec = match payload {
+		    [0] => Codec::Zlib,
+		    #[cfg(feature = "zstd")]
+		    [1] => Codec::Zstd,
+		    [_] => return Err(UnifontError::UnsupportedCodec),
+		    _ => return Err(UnifontError::MalformedHeader),
+		};
+	    }
+	    else if tag == CHECKSUM_TAG {
+		header.checksum = Some(u32::from_be_bytes(payload.try_into()
+				 .map_err(|_| UnifontError::MalformedHeader)?));
+	    }
+	}
+	Ok((header, 8 + length))
+    }
+    /// Returns the encoded header, or an empty `Vec` if there's nothing to put
+    /// in it.
+    #[cfg(feature = "std")]
+    pub fn encode(&self) -> Vec<u8> {
+	let mut records = Vec::new();
+	if let Some(version) = self.version.as_ref() {
+	    push_record(&mut records, VERSION_TAG, version.as_bytes());
+	}
+	if let Some(glyph_count) = self.glyph_count {
+	    push_record(&mut records, GLYPH_COUNT_TAG, &glyph_count.to_be_bytes());
+	}
+	match self.codec {
+	    Codec::Zlib => (),
+	    #[cfg(feature = "zstd")]
+	    Codec::Zstd => push_record(&mut records, CODEC_TAG, &[1]),
+	}
+	if let Some(checksum) = self.checksum {
+	    push_record(&mut records, CHECKSUM_TAG, &checksum.to_be_bytes());
+	}
+	if records.is_empty() { return records }
+	let mut ret = Vec::with_capacity(8 + records.len());
+	ret.extend_from_slice(HEADER_MAGIC);
+	ret.extend_from_slice(&(records.len() as u32).to_be_bytes());
+	ret.extend_from_slice(&records);
+	ret
+    }
+}
+
+#[cfg(feature = "std")]
+fn push_record(out: &mut Vec<u8>, tag: &[u8; 4], payload: &[u8]) {
+    out.extend_from_slice(tag);
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    out.extend_from_slice(payload);
+}
@@ -0,0 +1,50 @@
+//! Simple text layout on top of `Unifont::measure_str`.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::Unifont;
+
+impl Unifont {
+    /// Word-wraps the given string so that every line is at most
+    /// `max_width_px` pixels wide, as measured by `measure_str` (so invisible
+    /// characters take up no space). Loads pages as necessary.
+    ///
+    /// Lines are broken greedily at ASCII spaces. Runs of spaces between words
+    /// become a single space, and spaces at the start or end of a line are
+    /// dropped. A word too wide to fit on a line by itself is broken wherever
+    /// it has to be, instead of overflowing. (A single character wider than
+    /// `max_width_px` still gets a line of its own.) Each `\n` in the input
+    /// starts a new line, so blank lines are kept.
+    pub fn wrap_str(&mut self, s: &str, max_width_px: u32) -> Vec<String> {
+	let space_width = self.glyph_width(' ' as u32);
+	let mut ret = Vec::new();
+	for paragraph in s.split('\n') {
+	    let mut line = String::new();
+	    let mut line_width = 0;
+	    for word in paragraph.split(' ').filter(|x| !x.is_empty()) {
+		let word_width = self.measure_str(word);
+		if !line.is_empty() {
+		    if line_width + space_width + word_width <= max_width_px {
+			line.push(' ');
+			line.push_str(word);
+			line_width += space_width + word_width;
+			continue
+		    }
+		    ret.push(core::mem::take(&mut line));
+		}
+		line_width = 0;
+		for c in word.chars() {
+		    let width = self.advance(c);
+		    if !line.is_empty() && line_width + width > max_width_px {
+			ret.push(core::mem::take(&mut line));
+			line_width = 0;
+		    }
+		    line.push(c);
+		    line_width += width;
+		}
+	    }
+	    ret.push(line);
+	}
+	ret
+    }
+}
@@ -1,13 +1,17 @@
 //! This crate incorporates the data for [GNU Unifont][1] in compressed binary
-//! form. It concerns itself with glyph lookup and caching only. It does not
-//! provide any rendering (like [`sdl2-unifont`][2] does) or even pixel lookup
-//! (like [`unifont`][3] does). It is nothing more than a compression scheme
-//! for the raw binary data represented in the `.hex` files that comprise
-//! GNU Unifont's "source code".
+//! form, and looks up and caches its glyphs. At heart, it is a compression
+//! scheme for the raw binary data represented in the `.hex` files that comprise
+//! GNU Unifont's "source code", but each glyph it returns also comes with the
+//! basics for putting it to use: pixel lookup, previews for the terminal,
+//! scaling and other simple transformations, blitting into your own pixel
+//! buffers, and, with the right features, conversion to an image or drawing
+//! with [`embedded-graphics`][2]. There are also some helpers for measuring
+//! and wrapping a line of text. For a ready-made SDL renderer, see
+//! [`sdl2-unifont`][3].
 //!
 //! [1]: http://unifoundry.com/unifont/index.html
-//! [2]: https://crates.io/crates/sdl2-unifont
-//! [3]: https://crates.io/crates/unifont
+//! [2]: https://crates.io/crates/embedded-graphics
+//! [3]: https://crates.io/crates/sdl2-unifont
 //!
 //! # Background
 //!
@@ -30,9 +34,9 @@
 //! rendering system may also have to account for [bidirectional text][4] (and
 //! right-to-left scripts in general) and take special care when [breaking
 //! lines of text][5]. Not to mention "invisible characters". All of these
-//! concerns are outside the scope of this crate, which, again, has the sole
-//! and simple purpose of retrieving the individual GNU Unifont glyph that
-//! represents a given Unicode code point.
+//! concerns are outside the scope of this crate, which deals only in the
+//! individual GNU Unifont glyph that represents a given Unicode code point,
+//! and in setting those glyphs side by side.
 //!
 //! [4]: https://unicode.org/reports/tr9/
 //! [5]: https://unicode.org/reports/tr14/
@@ -52,17 +56,106 @@
 //! use unifont_bitmap::Unifont;
 //! let mut unifont = Unifont::open();
 //! // Get a bitmap, loading its page if necessary. Requires mut.
-//! let my_bitmap = unifont.load_bitmap('井' as u32);
+//! let my_bitmap = unifont.load_char('井');
 //! println!("{} pixels wide.", if my_bitmap.is_wide() { 16 } else { 8 });
 //! println!("Bytes: {:?}", my_bitmap.get_bytes());
 //! // Get a bitmap, iff its page is already loaded. Does not require mut.
-//! let my_bitmap = unifont.get_bitmap('井' as u32).unwrap();
+//! let my_bitmap = unifont.get_char('井').unwrap();
 //! println!("{} pixels wide.", if my_bitmap.is_wide() { 16 } else { 8 });
 //! println!("Bytes: {:?}", my_bitmap.get_bytes());
 //! ```
 //!
+//! If you're working with raw codepoints instead of `char`s, `load_bitmap` and
+//! `get_bitmap` do the same thing with a `u32`.
+//!
+//! If all you need is printable ASCII, `ascii_bitmap` returns those glyphs
+//! from a plain table, without decompressing or allocating anything.
+//!
+//! For multithreaded usage, either load everything up front and share a
+//! `FrozenUnifont` (see `Unifont::into_frozen`), or share a `SyncUnifont`,
+//! which loads pages on demand without needing `mut`.
+//!
 //! What you do from here is complicated, and outside this crate's pay grade.
 //!
+//! If you only need a few scripts, you can embed a smaller font instead. Use
+//! the `compile-font` tool in this crate's repository to compile only the pages
+//! you need, e.g. Latin, Greek, Cyrillic, and the CJK Unified Ideographs:
+//!
+//! ```text
+//! cd compile-font
+//! cargo run --release -- --pages 00-05,4E-9F myfont.dat unifont.hex
+//! ```
+//!
+//! Then turn off this crate's default features (keeping `std` if you want it),
+//! so the full font isn't embedded, and embed yours with
+//! `Unifont::with_data(include_bytes!("myfont.dat"))`. Codepoints that you left
+//! out get the replacement glyph, which `compile-font` always keeps.
+//!
+//! # Optional features
+//!
+//! - `std` (enabled by default): Adds everything that needs the standard
+//!   library: `Unifont::from_reader`, `FontBuilder` and `compile_from_hex`,
+//!   `Atlas`, and the `std::error::Error` impl for `UnifontError`. Without
+//!   it, the crate is `no_std`, and only needs `alloc`. Loading, looking up,
+//!   and drawing glyphs all still work.
+//!
+//! - `embed-data` (enabled by default): Embeds the font data in your
+//!   executable, and adds `Unifont::open`. Without it, you have to provide
+//!   the font data yourself, with `Unifont::with_data`,
+//!   `Unifont::from_bytes`, or `Unifont::from_reader`, e.g. by shipping this
+//!   crate's `unifont.dat` alongside your executable.
+//!
+//! - `bmp-only`: Embeds only the glyphs in the Basic Multilingual Plane
+//!   (U+0000 through U+FFFF), which cuts the embedded data from about 900KB
+//!   to about 750KB. Codepoints outside the BMP get the replacement glyph.
+//!   Implies `embed-data`.
+//!
+//! - `mmap`: Adds `Unifont::from_mmap`, which memory-maps a `.dat` file
+//!   using the [`memmap2`][8] crate instead of reading it onto the heap.
+//!   Implies `std`.
+//!
+//! - `image`: Adds `Bitmap::to_image` and `Bitmap::to_rgba_image`, which
+//!   convert a bitmap into an image from the [`image`][6] crate.
+//!
+//! - `png`: Adds `Bitmap::to_png_bytes` and `Bitmap::save_png`, for dumping
+//!   glyphs to PNG files. Implies `image` and `std`.
+//!
+//! - `embedded-graphics`: Adds `Bitmap::pixels` and the `Glyph` drawable,
+//!   for drawing bitmaps with the [`embedded-graphics`][7] crate.
+//!
+//! - `zstd`: Adds `Codec::Zstd`, for reading and writing font data that is
+//!   compressed with [Zstandard][10] instead of zlib. For the full font,
+//!   Zstandard data is about 5% smaller and decompresses about a third
+//!   faster. The embedded font data still uses zlib, so that the default
+//!   build doesn't need a C compiler. Implies `std`. To compile font data
+//!   that uses it, build `compile-font` with its own `zstd` feature, and
+//!   pass it `--codec zstd`.
+//!
+//! - `checksum`: Verifies the CRC-32 that `compile-font` stores in the
+//!   font data's header whenever the page table is read, returning
+//!   `UnifontError::ChecksumMismatch` if the data is corrupted. This costs
+//!   a pass over all of the font data (well under a millisecond for the
+//!   full font in an optimized build), so it's off by default.
+//!
+//! - `ansi`: Adds `Bitmap::render_ansi`, which renders a glyph as half
+//!   blocks colored with 24-bit ANSI escape sequences, for previewing
+//!   glyphs in true color in a terminal.
+//!
+//! - `layout`: Adds `Unifont::wrap_str`, which word-wraps a string to a
+//!   given width in pixels.
+//!
+//! - `serde`: Implements `Serialize` and `Deserialize` for `BitmapBuf`,
+//!   using the [`serde`][9] crate.
+//!
+//! - `global`: Adds the `global` function, which returns a shared
+//!   `SyncUnifont` instance that is opened on first use.
+//!
+//! [6]: https://crates.io/crates/image
+//! [7]: https://crates.io/crates/embedded-graphics
+//! [8]: https://crates.io/crates/memmap2
+//! [9]: https://crates.io/crates/serde
+//! [10]: https://facebook.github.io/zstd/
+//!
 //! # Legalese
 //!
 //! The `unifont-bitmap` crate is copyright 2021, Solra Bizna, and licensed
@@ -99,9 +192,51 @@
 //! in the Apache-2.0 license, shall be dual licensed as above, without any
 //! additional terms or conditions.
 
-use byteorder::{ReadBytesExt, BigEndian};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    boxed::Box, collections::BTreeMap, format, string::String, sync::Arc,
+    vec, vec::Vec,
+};
+use byteorder::{ByteOrder, BigEndian};
+use once_cell::race::OnceBox;
+use header::FontHeader;
+#[cfg(feature = "embedded-graphics")]
+use embedded_graphics::{
+    Drawable, Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    pixelcolor::BinaryColor,
+    primitives::Rectangle,
+};
 
+mod ascii;
+mod codepoint;
+#[cfg(feature = "std")]
+mod atlas;
+#[cfg(feature = "std")]
+mod compile;
+mod header;
+#[cfg(feature = "layout")]
+mod layout;
+mod mirror;
+mod width;
+pub use ascii::{ascii_bitmap, ASCII_BITMAPS, REPLACEMENT_BITMAP};
+pub use codepoint::{CodePoint, InvalidCodePoint};
+pub use mirror::mirror_codepoint;
+pub use width::ea_width;
+#[cfg(feature = "std")]
+pub use atlas::Atlas;
+#[cfg(feature = "std")]
+pub use compile::{compile_from_hex, CompileStats, FontBuilder};
+pub use header::Codec;
+
+#[cfg(all(feature = "embed-data", not(feature = "bmp-only")))]
 const UNIFONT_DATA: &[u8] = include_bytes!("unifont.dat");
+#[cfg(feature = "bmp-only")]
+const UNIFONT_DATA: &[u8] = include_bytes!("unifont-bmp.dat");
 
 /// The largest codepoint value that is, or ever will be, legal in Unicode.
 pub const MAX_UNICODE_CODEPOINT: u32 = 0x10FFFF;
@@ -112,10 +247,30 @@ pub const MAX_UNICODE_PAGE: u32 = NUM_UNICODE_PAGES-1;
 /// The number of 256-codepoint "pages" that exist in Unicode.
 pub const NUM_UNICODE_PAGES: u32 = NUM_UNICODE_CODEPOINTS >> 8;
 
+/// The number of rows of a glyph that are above the baseline, as in upstream
+/// Unifont's `FONT_ASCENT`. Rows 0 through 13 are above the baseline.
+pub const UNIFONT_ASCENT: u32 = 14;
+/// The number of rows of a glyph that are below the baseline, as in upstream
+/// Unifont's `FONT_DESCENT`. Rows 14 and 15 are below the baseline, where
+/// descenders go.
+pub const UNIFONT_DESCENT: u32 = 2;
+/// The row that letters without descenders (like `A` or `x`) sit on: the
+/// lowest row above the baseline. To line Unifont up with another font, put
+/// the bottom of this row on the other font's baseline.
+pub const UNIFONT_BASELINE_ROW: u32 = UNIFONT_ASCENT - 1;
+/// The row that `Bitmap::with_underline` inks: the first row below the
+/// baseline, where Unifont draws `_`.
+pub const UNIFONT_UNDERLINE_ROW: u32 = UNIFONT_BASELINE_ROW + 1;
+/// The row that `Bitmap::with_strikethrough` inks: the middle of the
+/// x-height, where Unifont draws `-`.
+pub const UNIFONT_STRIKETHROUGH_ROW: u32 = 9;
+
 /// A single 8x16 or 16x16 bitmap, corresponding to a single displayed glyph.
 /// See the module documentation for a cryptic warning about combining
 /// characters, invisible characters, etc.
-#[derive(PartialEq,Eq)]
+///
+/// This only borrows the glyph's bytes, so it's as cheap to copy as a slice.
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
 pub struct Bitmap<'a> {
     bytes: &'a [u8],
 }
@@ -128,13 +283,50 @@ impl<'a> core::fmt::Debug for Bitmap<'a> {
     }
 }
 
+/// Renders the bitmap as ASCII art: 16 lines, separated by newlines, with `#`
+/// for each inked pixel and a space for each uninked one. Narrow glyphs are
+/// 8 characters wide, wide glyphs are 16.
+impl<'a> core::fmt::Display for Bitmap<'a> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+	let width = self.width();
+	for y in 0 .. 16 {
+	    if y != 0 { fmt.write_str("\n")? }
+	    for x in 0 .. width {
+		fmt.write_str(if self.get_pixel(x, y) { "#" } else { " " })?;
+	    }
+	}
+	Ok(())
+    }
+}
+
 impl<'a> Bitmap<'a> {
     /// Returns the bytes that make up the given bitmap. Each byte contains 8
     /// pixels. The highest order bit of the byte is the leftmost pixel, the
     /// next highest order bit is the next pixel, and so on. If the glyph is
     /// wide (see `is_wide`) then there are two bytes per row, otherwise there
     /// is one byte per row.
+    ///
+    /// So a wide glyph has 32 bytes, and a narrow glyph has 16, with no bytes
+    /// at all for its (nonexistent) right half. If you'd rather treat every
+    /// glyph as wide, use `get_bytes_padded`.
     pub fn get_bytes(&self) -> &'a [u8] { self.bytes }
+    /// Returns the bytes that make up the given bitmap, always in the wide
+    /// layout: two bytes per row, 32 bytes in all. If the glyph is narrow, the
+    /// second byte of each row (its nonexistent right half) is zero.
+    pub fn get_bytes_padded(&self) -> [u8; 32] {
+	let mut ret = [0; 32];
+	for (y, out) in ret.chunks_exact_mut(2).enumerate() {
+	    out.copy_from_slice(&self.get_row(y as u32).to_be_bytes());
+	}
+	ret
+    }
+    /// Makes an owned copy of this bitmap, which doesn't borrow the `Unifont`
+    /// it came from.
+    pub fn to_owned(&self) -> BitmapBuf {
+	let mut bytes = [0; 32];
+	bytes[.. self.bytes.len()].copy_from_slice(self.bytes);
+	BitmapBuf { bytes, wide: self.is_wide() }
+    }
     /// Returns `true` if the bitmap is wide (16x16), `false` if it is narrow
     /// (8x16).
     pub fn is_wide(&self) -> bool {
@@ -152,20 +344,987 @@ impl<'a> Bitmap<'a> {
 	    true => (16.into(), 16.into()),
 	}
     }
+    /// Returns the width of the bitmap in pixels: 8 if it's narrow, 16 if
+    /// it's wide.
+    pub fn width(&self) -> u32 {
+	if self.is_wide() { 16 } else { 8 }
+    }
+    /// Returns the height of the bitmap in pixels, which is always 16.
+    pub fn height(&self) -> u32 { 16 }
+    /// Returns `true` if the pixel at the given coordinates is inked, `false`
+    /// if it isn't. (0,0) is the upper-left pixel of the glyph.
+    ///
+    /// Coordinates outside the bitmap's dimensions (see `get_dimensions`) are
+    /// never inked, and return `false` rather than panicking. This means you
+    /// can treat a narrow glyph as a wide one whose right half is blank.
+    pub fn get_pixel(&self, x: u32, y: u32) -> bool {
+	let (width, height) = self.get_dimensions::<u32>();
+	if x >= width || y >= height { return false }
+	let pitch = width / 8;
+	let byte = self.bytes[(y * pitch + x / 8) as usize];
+	byte & (0x80 >> (x % 8)) != 0
+    }
+    /// Returns the given row of the bitmap, packed into a `u16`. The leftmost
+    /// pixel is in the highest order bit. If the glyph is narrow, the low 8
+    /// bits (its nonexistent right half) are always zero.
+    ///
+    /// **PANICS** if you pass a `y` of 16 or greater.
+    pub fn get_row(&self, y: u32) -> u16 {
+	assert!(y < 16);
+	let y = y as usize;
+	if self.is_wide() {
+	    u16::from_be_bytes([self.bytes[y * 2], self.bytes[y * 2 + 1]])
+	}
+	else {
+	    (self.bytes[y] as u16) << 8
+	}
+    }
+    /// Returns the given column of the bitmap, packed into a `u16`. The top
+    /// pixel is in the highest order bit.
+    ///
+    /// **PANICS** if you pass an `x` that is not less than the glyph's width
+    /// (see `width`).
+    pub fn get_column(&self, x: u32) -> u16 {
+	assert!(x < self.width());
+	(0 .. 16).fold(0, |column, y| {
+	    column << 1 | ((self.get_row(y) >> (15 - x)) & 1)
+	})
+    }
+    /// Returns an iterator over the coordinates of every inked pixel, as
+    /// `(x, y)` pairs, in row-major order (left to right, then top to
+    /// bottom). Allocates nothing.
+    pub fn iter_set_pixels(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+	let width = self.get_dimensions::<u8>().0;
+	(0 .. 16u8).flat_map(move |y| {
+	    let row = self.get_row(y as u32);
+	    (0 .. width).filter(move |x| row & (0x8000 >> x) != 0)
+		.map(move |x| (x, y))
+	})
+    }
+    /// Returns the bitmap as a grid of booleans, indexed as `grid[y][x]`.
+    /// `true` is inked, `false` is not. Columns 8 through 15 of a narrow
+    /// glyph are always `false`.
+    pub fn to_bool_grid(&self) -> [[bool; 16]; 16] {
+	let mut grid = [[false; 16]; 16];
+	for (x, y) in self.iter_set_pixels() {
+	    grid[y as usize][x as usize] = true;
+	}
+	grid
+    }
+    /// Returns `true` if no pixels are inked, e.g. for a space.
+    pub fn is_blank(&self) -> bool {
+	self.bytes.iter().all(|&x| x == 0)
+    }
+    /// Returns `true` if the two bitmaps ink exactly the same pixels, even if
+    /// one is narrow and the other is wide. A narrow glyph is visually equal
+    /// to a wide glyph whose right half is blank, and is otherwise the same.
+    /// (`==` considers those different, since their bytes differ.)
+    pub fn visually_eq(&self, other: &Bitmap) -> bool {
+	(0 .. 16).all(|y| self.get_row(y) == other.get_row(y))
+    }
+    /// Returns the number of inked pixels, out of 128 for a narrow glyph or 256
+    /// for a wide one.
+    pub fn ink_count(&self) -> u32 {
+	self.bytes.iter().map(|x| x.count_ones()).sum()
+    }
+    /// Returns the smallest rectangle containing every inked pixel, as
+    /// `(min_x, min_y, max_x, max_y)`, inclusive. Returns `None` if no pixels
+    /// are inked.
+    pub fn ink_bounds(&self) -> Option<(u8, u8, u8, u8)> {
+	self.iter_set_pixels().fold(None, |bounds, (x, y)| match bounds {
+	    None => Some((x, y, x, y)),
+	    Some((min_x, min_y, max_x, max_y))
+		=> Some((min_x.min(x), min_y.min(y),
+			 max_x.max(x), max_y.max(y))),
+	})
+    }
+    /// Renders the bitmap as 8 lines of text, separated by newlines, packing
+    /// two rows of pixels into each character using half blocks: `▀` for an
+    /// inked upper pixel, `▄` for an inked lower pixel, `█` for both, and a
+    /// space for neither. In most terminals, this results in a much more
+    /// compact and correctly proportioned preview than the `Display` impl.
+    pub fn render_halfblock(&self) -> String {
+	let width = self.width();
+	let mut ret = String::with_capacity(8 * (width as usize * 3 + 1));
+	for y in 0 .. 8 {
+	    if y != 0 { ret.push('\n') }
+	    for x in 0 .. width {
+		ret.push(match (self.get_pixel(x, y * 2),
+				self.get_pixel(x, y * 2 + 1)) {
+		    (false, false) => ' ',
+		    (true, false) => '\u{2580}',
+		    (false, true) => '\u{2584}',
+		    (true, true) => '\u{2588}',
+		});
+	    }
+	}
+	ret
+    }
+    /// Renders the bitmap as 4 lines of Braille patterns (`U+2800` through
+    /// `U+28FF`), separated by newlines. Each character represents a 2x4
+    /// block of pixels, using the standard dot numbering, so a narrow glyph
+    /// becomes 4 characters wide and a wide glyph becomes 8. This is the
+    /// densest way to preview a glyph in a terminal.
+    pub fn render_braille(&self) -> String {
+	// (x, y) offset within the cell → bit, in order of dot number
+	const DOTS: [(u32, u32, u32); 8] = [
+	    (0, 0, 0x01), (0, 1, 0x02), (0, 2, 0x04), (1, 0, 0x08),
+	    (1, 1, 0x10), (1, 2, 0x20), (0, 3, 0x40), (1, 3, 0x80),
+	];
+	let width = self.width();
+	let mut ret = String::with_capacity(4 * (width as usize / 2 * 3 + 1));
+	for cell_y in 0 .. 4 {
+	    if cell_y != 0 { ret.push('\n') }
+	    for cell_x in 0 .. width / 2 {
+		let mut pattern = 0;
+		for &(x, y, bit) in DOTS.iter() {
+		    if self.get_pixel(cell_x * 2 + x, cell_y * 4 + y) {
+			pattern |= bit;
+		    }
+		}
+		ret.push(char::from_u32(0x2800 + pattern).unwrap());
+	    }
+	}
+	ret
+    }
+    /// Like `render_halfblock`, but colors every half block with 24-bit ANSI
+    /// escape sequences, so that inked pixels are `fg` and uninked pixels are
+    /// `bg`, given as `(red, green, blue)`. Each of the 8 lines is as many
+    /// characters wide as the glyph is pixels wide, and ends by resetting the
+    /// colors, so the glyph doesn't bleed into whatever is printed next.
+    ///
+    /// The terminal must support 24-bit ("true") color. Most modern ones do.
+    ///
+    /// Only available with the `ansi` feature.
+    #[cfg(feature = "ansi")]
+    pub fn render_ansi(&self, fg: (u8, u8, u8), bg: (u8, u8, u8)) -> String {
+	use core::fmt::Write;
+	let width = self.width();
+	let mut ret = String::new();
+	for y in 0 .. 8 {
+	    if y != 0 { ret.push('\n') }
+	    // only emit a color when it changes from the previous cell
+	    let mut current = None;
+	    for x in 0 .. width {
+		let upper = if self.get_pixel(x, y * 2) { fg } else { bg };
+		let lower = if self.get_pixel(x, y * 2 + 1) { fg } else { bg };
+		if current != Some((upper, lower)) {
+		    write!(ret, "\x1B[38;2;{};{};{};48;2;{};{};{}m",
+			   upper.0, upper.1, upper.2,
+			   lower.0, lower.1, lower.2).unwrap();
+		    current = Some((upper, lower));
+		}
+		ret.push('\u{2580}');
+	    }
+	    ret.push_str("\x1B[0m");
+	}
+	ret
+    }
+    /// Draws the bitmap into an RGBA buffer, `dest_width` pixels wide and four
+    /// bytes per pixel, with the upper-left corner of the glyph at `(x, y)`.
+    /// Inked pixels are set to `fg`. Uninked pixels are set to `bg`, or left
+    /// untouched if `bg` is `None`.
+    ///
+    /// The glyph is clipped at the edges of the buffer. The buffer's height is
+    /// inferred from its length.
+    pub fn blit_rgba(&self, dest: &mut [u8], dest_width: usize,
+		     x: usize, y: usize, fg: [u8; 4], bg: Option<[u8; 4]>) {
+	self.blit_with(dest.len() / 4, dest_width, x, y, |i, inked| {
+	    let color = if inked { fg } else {
+		match bg { Some(bg) => bg, None => return }
+	    };
+	    dest[i * 4 .. i * 4 + 4].copy_from_slice(&color);
+	});
+    }
+    /// Draws the bitmap into an 8-bit alpha (coverage) buffer, `dest_width`
+    /// pixels wide and one byte per pixel, with the upper-left corner of the
+    /// glyph at `(x, y)`. Inked pixels are set to `0xFF`. Uninked pixels are
+    /// left untouched.
+    ///
+    /// The glyph is clipped at the edges of the buffer. The buffer's height is
+    /// inferred from its length.
+    pub fn blit_alpha(&self, dest: &mut [u8], dest_width: usize,
+		      x: usize, y: usize) {
+	self.blit_with(dest.len(), dest_width, x, y, |i, inked| {
+	    if inked { dest[i] = 0xFF }
+	});
+    }
+    /// Draws the bitmap into a 1-bit-per-pixel buffer, `dest_stride_bytes`
+    /// bytes per row, with the upper-left corner of the glyph at pixel
+    /// `(x_bit, y)`. The bits are in the same order as `get_bytes`: the
+    /// highest order bit of each byte is the leftmost pixel. Inked pixels are
+    /// ORed in; nothing is ever cleared. This is the layout most monochrome
+    /// OLED and e-paper drivers want, and `x_bit` doesn't have to be a
+    /// multiple of 8.
+    ///
+    /// The glyph is clipped at the edges of the buffer. The buffer's height is
+    /// inferred from its length.
+    pub fn blit_mono(&self, dest: &mut [u8], dest_stride_bytes: usize,
+		     x_bit: usize, y: usize) {
+	if dest_stride_bytes == 0 { return }
+	let dest_height = dest.len() / dest_stride_bytes;
+	let first_byte = x_bit / 8;
+	let shift = x_bit % 8;
+	for row in 0 .. 16usize.min(dest_height.saturating_sub(y)) {
+	    // the row, shifted into place across three bytes
+	    let bits = ((self.get_row(row as u32) as u32) << 8) >> shift;
+	    let start = (y + row) * dest_stride_bytes;
+	    for (n, byte) in bits.to_be_bytes()[1..].iter().enumerate() {
+		if first_byte + n >= dest_stride_bytes { break }
+		dest[start + first_byte + n] |= byte;
+	    }
+	}
+    }
+    /// Scales the bitmap up by an integer factor, using nearest-neighbor
+    /// sampling. Returns a row-major grid of `factor * width` by `factor * 16`
+    /// pixels, `true` for inked and `false` for not.
+    ///
+    /// **PANICS** if you pass a `factor` of zero.
+    pub fn scale_nearest(&self, factor: u32) -> Vec<bool> {
+	assert!(factor >= 1);
+	let (width, height) = self.get_dimensions::<u32>();
+	let out_width = width * factor;
+	let out_height = height * factor;
+	let mut ret = Vec::with_capacity((out_width * out_height) as usize);
+	for y in 0 .. out_height {
+	    for x in 0 .. out_width {
+		ret.push(self.get_pixel(x / factor, y / factor));
+	    }
+	}
+	ret
+    }
+    /// Scales the bitmap to `out_w` by `out_h` pixels, averaging the source
+    /// pixels that each output pixel covers. Returns a row-major 8-bit
+    /// grayscale image, where 255 is fully inked and 0 is not inked at all.
+    /// Any size works, but this is meant for scaling down, e.g. to draw text
+    /// smaller than 16 pixels tall.
+    ///
+    /// Source pixels that are only partly covered by an output pixel count in
+    /// proportion to how much is covered, so sizes that don't divide the
+    /// glyph's evenly still look reasonable.
+    ///
+    /// **PANICS** if `out_w` or `out_h` is zero.
+    pub fn downscale_gray(&self, out_w: u32, out_h: u32) -> Vec<u8> {
+	assert!(out_w >= 1 && out_h >= 1);
+	let (width, height) = self.get_dimensions::<u32>();
+	// Work in units of 1/out_w source pixels horizontally, and 1/out_h
+	// vertically. Each output pixel is then `width` by `height` units, and
+	// each source pixel is `out_w` by `out_h` units.
+	let overlap = |out: u32, src: u32, out_size: u32, src_size: u32| {
+	    let start = (out * src_size).max(src * out_size);
+	    let end = ((out + 1) * src_size).min((src + 1) * out_size);
+	    end.saturating_sub(start)
+	};
+	let area = width * height;
+	let mut ret = Vec::with_capacity((out_w * out_h) as usize);
+	for oy in 0 .. out_h {
+	    let first_y = oy * height / out_h;
+	    let last_y = ((oy + 1) * height).div_ceil(out_h).min(height);
+	    for ox in 0 .. out_w {
+		let first_x = ox * width / out_w;
+		let last_x = ((ox + 1) * width).div_ceil(out_w).min(width);
+		let mut coverage = 0;
+		for y in first_y .. last_y {
+		    let weight_y = overlap(oy, y, out_h, height);
+		    for x in first_x .. last_x {
+			if self.get_pixel(x, y) {
+			    coverage += weight_y * overlap(ox, x, out_w, width);
+			}
+		    }
+		}
+		ret.push(((coverage * 255 + area / 2) / area) as u8);
+	    }
+	}
+	ret
+    }
+    /// Returns a line in the `.hex` format that GNU Unifont's source files
+    /// use, describing this bitmap as the glyph for the given codepoint. The
+    /// codepoint is written as 4 hex digits if it's in the BMP, 6 otherwise,
+    /// followed by a colon and 32 or 64 hex digits of bitmap data. There is no
+    /// trailing newline.
+    pub fn to_hex_line(&self, codepoint: u32) -> String {
+	use core::fmt::Write;
+	let mut ret = String::with_capacity(7 + self.bytes.len() * 2);
+	if codepoint <= 0xFFFF {
+	    write!(ret, "{:04X}:", codepoint).unwrap();
+	}
+	else {
+	    write!(ret, "{:06X}:", codepoint).unwrap();
+	}
+	for byte in self.bytes {
+	    write!(ret, "{:02X}", byte).unwrap();
+	}
+	ret
+    }
+    /// Returns the bitmap as a binary ("P4") [PBM][1] file, as wide as the
+    /// glyph and 16 pixels tall. Inked pixels are black.
+    ///
+    /// [1]: http://netpbm.sourceforge.net/doc/pbm.html
+    pub fn to_pbm(&self) -> Vec<u8> {
+	let (width, height) = self.get_dimensions::<u32>();
+	// conveniently, PBM's bit order and row padding are identical to ours
+	let mut ret = format!("P4\n{} {}\n", width, height).into_bytes();
+	ret.extend_from_slice(self.bytes);
+	ret
+    }
+    /// Returns the bitmap as an [XBM][1] file: C source code defining
+    /// `<name>_width`, `<name>_height`, and a `<name>_bits` array. `name`
+    /// should be a valid C identifier. Inked pixels are set bits.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/X_BitMap
+    pub fn to_xbm(&self, name: &str) -> String {
+	use core::fmt::Write;
+	let (width, height) = self.get_dimensions::<u32>();
+	let mut ret = format!("#define {0}_width {1}\n#define {0}_height {2}\n\
+			       static unsigned char {0}_bits[] = {{",
+			      name, width, height);
+	for (n, byte) in self.bytes.iter().enumerate() {
+	    // XBM puts the leftmost pixel in the least significant bit
+	    let sep = if n == 0 { "\n   " } else if n % 12 == 0 { ",\n   " }
+	    else { ", " };
+	    write!(ret, "{}0x{:02x}", sep, byte.reverse_bits()).unwrap();
+	}
+	ret.push_str(" };\n");
+	ret
+    }
+    /// Returns the bitmap as a 1-bit-per-pixel Windows BMP file, as wide as the
+    /// glyph and 16 pixels tall. Inked pixels are black, other pixels are
+    /// white.
+    pub fn to_bmp(&self) -> Vec<u8> {
+	let (width, height) = self.get_dimensions::<u32>();
+	let bytes_per_row = (width as usize).div_ceil(8);
+	// BMP rows are padded to four bytes
+	let padded_row = bytes_per_row.div_ceil(4) * 4;
+	let image_size = (padded_row * height as usize) as u32;
+	let data_offset = 14 + 40 + 8;
+	let mut ret = Vec::with_capacity((data_offset + image_size) as usize);
+	// file header
+	ret.extend_from_slice(b"BM");
+	ret.extend_from_slice(&(data_offset + image_size).to_le_bytes());
+	ret.extend_from_slice(&[0; 4]);
+	ret.extend_from_slice(&data_offset.to_le_bytes());
+	// BITMAPINFOHEADER
+	for field in [40, width, height] {
+	    ret.extend_from_slice(&field.to_le_bytes());
+	}
+	ret.extend_from_slice(&1u16.to_le_bytes()); // planes
+	ret.extend_from_slice(&1u16.to_le_bytes()); // bits per pixel
+	// compression, image size, pixels per meter (72 DPI), palette size
+	for field in [0, image_size, 2835, 2835, 2, 2] {
+	    ret.extend_from_slice(&field.to_le_bytes());
+	}
+	// palette: white, then black
+	ret.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0]);
+	// rows go from the bottom up, but the bit order is the same as ours
+	for row in self.bytes.chunks_exact(bytes_per_row).rev() {
+	    ret.extend_from_slice(row);
+	    ret.resize(ret.len() + padded_row - bytes_per_row, 0);
+	}
+	ret
+    }
+    /// Returns a mirror image of the bitmap, with its columns reversed, e.g.
+    /// for mirroring brackets in right-to-left text. The result is the same
+    /// width as the original.
+    pub fn flip_horizontal(&self) -> BitmapBuf {
+	let width = self.width();
+	BitmapBuf::from_rows(self.is_wide(), |y| {
+	    self.get_row(y).reverse_bits() << (16 - width)
+	})
+    }
+    /// Returns an upside-down copy of the bitmap, with its rows reversed,
+    /// e.g. for drawing into a buffer whose origin is at the bottom left. The
+    /// result is the same width as the original.
+    pub fn flip_vertical(&self) -> BitmapBuf {
+	BitmapBuf::from_rows(self.is_wide(), |y| self.get_row(15 - y))
+    }
+    /// Returns a synthetic bold copy of the bitmap, made by inking every pixel
+    /// to the right of an inked pixel. Pixels in the rightmost column don't
+    /// spill over, so the result is the same width as the original.
+    pub fn embolden(&self) -> BitmapBuf {
+	let mask = self.width_mask();
+	BitmapBuf::from_rows(self.is_wide(), |y| {
+	    let row = self.get_row(y);
+	    (row | (row >> 1)) & mask
+	})
+    }
+    /// Returns a synthetic italic copy of the bitmap, made by shifting the
+    /// top rows to the right: rows 0 through 3 by two pixels, and rows 4
+    /// through 8 by one. Rows 9 through 15, which include the baseline, stay
+    /// where they are.
+    ///
+    /// The result is the same width as the original, so pixels shifted past
+    /// the right edge are clipped. Most glyphs leave enough room on the right
+    /// that nothing is lost.
+    pub fn italicize(&self) -> BitmapBuf {
+	let mask = self.width_mask();
+	BitmapBuf::from_rows(self.is_wide(), |y| {
+	    (self.get_row(y) >> (13u32.saturating_sub(y) / 5)) & mask
+	})
+    }
+    /// Returns a negative copy of the bitmap, with every pixel flipped, e.g.
+    /// for drawing selected text in reverse video. The result is the same
+    /// width as the original; a narrow glyph only has its 8 columns flipped.
+    pub fn invert(&self) -> BitmapBuf {
+	let mask = self.width_mask();
+	BitmapBuf::from_rows(self.is_wide(), |y| self.get_row(y) ^ mask)
+    }
+    /// Returns a copy of the bitmap with `other` drawn on top of it: each
+    /// pixel is inked if it's inked in either bitmap. Useful for crudely
+    /// combining a base character with a combining mark, since Unifont draws
+    /// most combining marks in roughly the right place within the cell.
+    ///
+    /// If either bitmap is wide, the result is wide, with the narrow bitmap
+    /// in its left half.
+    pub fn overlay(&self, other: &Bitmap) -> BitmapBuf {
+	BitmapBuf::from_rows(self.is_wide() || other.is_wide(), |y| {
+	    self.get_row(y) | other.get_row(y)
+	})
+    }
+    /// Returns a copy of the bitmap with every pixel of row
+    /// `UNIFONT_UNDERLINE_ROW` inked. Since this row spans the whole width of
+    /// the glyph, underlines drawn under consecutive glyphs join up.
+    pub fn with_underline(&self) -> BitmapBuf {
+	self.with_row_inked(UNIFONT_UNDERLINE_ROW)
+    }
+    /// Returns a copy of the bitmap with every pixel of row
+    /// `UNIFONT_STRIKETHROUGH_ROW` inked.
+    pub fn with_strikethrough(&self) -> BitmapBuf {
+	self.with_row_inked(UNIFONT_STRIKETHROUGH_ROW)
+    }
+    fn with_row_inked(&self, inked_y: u32) -> BitmapBuf {
+	let mask = self.width_mask();
+	BitmapBuf::from_rows(self.is_wide(), |y| {
+	    if y == inked_y { mask } else { self.get_row(y) }
+	})
+    }
+    /// Returns a row with every pixel inside the bitmap's width inked.
+    fn width_mask(&self) -> u16 {
+	!0 << (16 - self.width())
+    }
+    /// Returns a copy of the bitmap rotated 90 degrees clockwise, e.g. for
+    /// vertical text.
+    ///
+    /// The result is always wide (16x16). A narrow glyph is rotated as if it
+    /// were a wide glyph with a blank right half, so its image ends up in the
+    /// top 8 rows, and the bottom 8 rows are blank.
+    pub fn rotate_90_cw(&self) -> BitmapBuf {
+	self.rotate(|x, y| (y, 15 - x))
+    }
+    /// Returns a copy of the bitmap rotated 90 degrees counterclockwise.
+    ///
+    /// The result is always wide (16x16). A narrow glyph is rotated as if it
+    /// were a wide glyph with a blank right half, so its image ends up in the
+    /// bottom 8 rows, and the top 8 rows are blank.
+    pub fn rotate_90_ccw(&self) -> BitmapBuf {
+	self.rotate(|x, y| (15 - y, x))
+    }
+    /// Builds a wide bitmap whose pixel at `(x, y)` is this bitmap's pixel at
+    /// `source(x, y)`.
+    fn rotate<F: Fn(u32, u32) -> (u32, u32)>(&self, source: F) -> BitmapBuf {
+	BitmapBuf::from_rows(true, |y| {
+	    (0 .. 16).filter(|&x| {
+		let (source_x, source_y) = source(x, y);
+		self.get_pixel(source_x, source_y)
+	    }).fold(0, |row, x| row | (0x8000 >> x))
+	})
+    }
+    /// Calls `f` with the index of each destination pixel the glyph covers, in
+    /// a buffer of `dest_len` pixels that is `dest_width` pixels wide, along
+    /// with whether that pixel is inked. Clips at the edges of the buffer.
+    fn blit_with<F: FnMut(usize, bool)>(&self, dest_len: usize,
+					dest_width: usize, x: usize, y: usize,
+					mut f: F) {
+	if dest_width == 0 { return }
+	let dest_height = dest_len / dest_width;
+	let width = self.get_dimensions::<usize>().0;
+	for src_y in 0 .. 16 {
+	    let dest_y = y + src_y;
+	    if dest_y >= dest_height { break }
+	    for src_x in 0 .. width {
+		let dest_x = x + src_x;
+		if dest_x >= dest_width { break }
+		f(dest_y * dest_width + dest_x,
+		  self.get_pixel(src_x as u32, src_y as u32));
+	    }
+	}
+    }
+}
+
+#[cfg(feature = "image")]
+impl<'a> Bitmap<'a> {
+    /// Converts the bitmap into a grayscale image, as wide as the glyph and
+    /// 16 pixels tall. Inked pixels are 255, uninked pixels are 0.
+    pub fn to_image(&self) -> image::GrayImage {
+	let (width, height) = self.get_dimensions::<u32>();
+	let mut ret = image::GrayImage::new(width, height);
+	self.blit_alpha(&mut ret, width as usize, 0, 0);
+	ret
+    }
+    /// Converts the bitmap into an RGBA image, as wide as the glyph and 16
+    /// pixels tall. Inked pixels are `fg`, uninked pixels are `bg`.
+    pub fn to_rgba_image(&self, fg: [u8; 4], bg: [u8; 4]) -> image::RgbaImage {
+	let (width, height) = self.get_dimensions::<u32>();
+	let mut ret = image::RgbaImage::new(width, height);
+	self.blit_rgba(&mut ret, width as usize, 0, 0, fg, Some(bg));
+	ret
+    }
+}
+
+#[cfg(feature = "png")]
+impl<'a> Bitmap<'a> {
+    /// Encodes the grayscale image from `to_image` as a PNG file, and returns
+    /// its bytes.
+    pub fn to_png_bytes(&self) -> Vec<u8> {
+	let mut ret = std::io::Cursor::new(Vec::new());
+	self.to_image().write_to(&mut ret, image::ImageFormat::Png)
+	    .expect("encoding a PNG into a Vec should never fail");
+	ret.into_inner()
+    }
+    /// Saves the grayscale image from `to_image` as a PNG file at the given
+    /// path, whatever its extension.
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P)
+					       -> image::ImageResult<()> {
+	self.to_image().save_with_format(path, image::ImageFormat::Png)
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl<'a> Bitmap<'a> {
+    /// Returns an iterator over every pixel in the glyph's cell, positioned
+    /// with the upper-left corner of the glyph at `origin`. Inked pixels are
+    /// `BinaryColor::On`, uninked pixels are `BinaryColor::Off`. (If you want
+    /// a transparent background, filter out the `Off` pixels.)
+    pub fn pixels(&self, origin: Point)
+		  -> impl Iterator<Item = Pixel<BinaryColor>> + '_ {
+	let (width, height) = self.get_dimensions::<u32>();
+	(0 .. height).flat_map(move |y| {
+	    (0 .. width).map(move |x| {
+		Pixel(origin + Point::new(x as i32, y as i32),
+		      BinaryColor::from(self.get_pixel(x, y)))
+	    })
+	})
+    }
+}
+
+/// A bitmap, positioned for drawing with [`embedded-graphics`][1]. Drawing it
+/// returns the position of the next glyph on the line, which is 8 or 16
+/// pixels to the right depending on whether the glyph is narrow or wide.
+///
+/// [1]: https://crates.io/crates/embedded-graphics
+#[cfg(feature = "embedded-graphics")]
+pub struct Glyph<'a> {
+    /// The bitmap to draw.
+    pub bitmap: Bitmap<'a>,
+    /// Where to put the upper-left corner of the glyph.
+    pub position: Point,
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl<'a> Dimensions for Glyph<'a> {
+    fn bounding_box(&self) -> Rectangle {
+	let (width, height) = self.bitmap.get_dimensions::<u32>();
+	Rectangle::new(self.position, Size::new(width, height))
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl<'a> Drawable for Glyph<'a> {
+    type Color = BinaryColor;
+    type Output = Point;
+    fn draw<D>(&self, target: &mut D) -> Result<Point, D::Error>
+    where D: DrawTarget<Color = BinaryColor> {
+	target.draw_iter(self.bitmap.pixels(self.position))?;
+	let advance = self.bitmap.get_dimensions::<i32>().0;
+	Ok(self.position + Point::new(advance, 0))
+    }
+}
+
+impl<'a> core::ops::Index<(u32, u32)> for Bitmap<'a> {
+    type Output = bool;
+    /// Returns whether the pixel at `(x, y)` is inked, like `get_pixel`.
+    ///
+    /// **PANICS** if the coordinates are outside the bitmap's dimensions.
+    fn index(&self, (x, y): (u32, u32)) -> &bool {
+	let (width, height) = self.get_dimensions::<u32>();
+	if x >= width || y >= height {
+	    panic!("pixel ({}, {}) is out of bounds for a {}x{} glyph",
+		   x, y, width, height);
+	}
+	if self.get_pixel(x, y) { &true } else { &false }
+    }
+}
+
+/// An owned copy of a `Bitmap`, which doesn't borrow the `Unifont` it came
+/// from. It can be kept around after its page is purged, stored in your own
+/// caches, or sent to other threads.
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+	   serde(into = "BitmapBufRepr", try_from = "BitmapBufRepr"))]
+pub struct BitmapBuf {
+    bytes: [u8; 32],
+    wide: bool,
+}
+
+impl core::fmt::Debug for BitmapBuf {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+	core::fmt::Debug::fmt(&self.as_bitmap(), fmt)
+    }
+}
+
+impl BitmapBuf {
+    /// Combines a base glyph with any number of combining mark glyphs, by
+    /// overlaying them all (see `Bitmap::overlay`). The result is wide if any
+    /// of the glyphs are.
+    ///
+    /// **This is not real text shaping.** The marks are drawn exactly where
+    /// Unifont put them within their own cells. That works for a single
+    /// accent on a typical lowercase letter, but accents on capitals or tall
+    /// letters may collide with the base glyph, multiple marks above or below
+    /// the base will be drawn on top of each other instead of stacked, and
+    /// marks that Unifont draws outside the cell (or not at all) won't show
+    /// up.
+    pub fn stack(base: &Bitmap, marks: &[Bitmap]) -> BitmapBuf {
+	marks.iter().fold(base.to_owned(),
+			  |ret, mark| ret.as_bitmap().overlay(mark))
+    }
+    /// Creates a bitmap from 16 rows, packed as by `Bitmap::get_row`. If it's
+    /// narrow, the low 8 bits of each row are ignored.
+    fn from_rows<F: FnMut(u32) -> u16>(wide: bool, mut row: F) -> BitmapBuf {
+	let mut ret = BitmapBuf { bytes: [0; 32], wide };
+	for y in 0 .. 16 {
+	    let [hi, lo] = row(y).to_be_bytes();
+	    if wide {
+		ret.bytes[y as usize * 2] = hi;
+		ret.bytes[y as usize * 2 + 1] = lo;
+	    }
+	    else {
+		ret.bytes[y as usize] = hi;
+	    }
+	}
+	ret
+    }
+    /// Creates a bitmap from the given bytes, in the same format as
+    /// `Bitmap::get_bytes`: 16 bytes for a narrow bitmap, or 32 bytes for a
+    /// wide one. Returns `None` if there are any other number of bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Option<BitmapBuf> {
+	let wide = match bytes.len() {
+	    16 => false,
+	    32 => true,
+	    _ => return None,
+	};
+	let mut ret = BitmapBuf { bytes: [0; 32], wide };
+	ret.bytes[.. bytes.len()].copy_from_slice(bytes);
+	Some(ret)
+    }
+    /// Returns the bytes that make up the given bitmap, in the same format as
+    /// `Bitmap::get_bytes`.
+    pub fn get_bytes(&self) -> &[u8] {
+	&self.bytes[.. if self.wide { 32 } else { 16 }]
+    }
+    /// Returns `true` if the bitmap is wide (16x16), `false` if it is narrow
+    /// (8x16).
+    pub fn is_wide(&self) -> bool { self.wide }
+    /// Returns the dimensions of the bitmap, width then height.
+    /// Always returns (8,16) or (16,16).
+    pub fn get_dimensions<T: From<u8>>(&self) -> (T, T) {
+	self.as_bitmap().get_dimensions()
+    }
+    /// Returns the width of the bitmap in pixels: 8 if it's narrow, 16 if
+    /// it's wide.
+    pub fn width(&self) -> u32 { self.as_bitmap().width() }
+    /// Returns the height of the bitmap in pixels, which is always 16.
+    pub fn height(&self) -> u32 { 16 }
+    /// Borrows this bitmap as a `Bitmap`, giving access to all of the pixel
+    /// access and rendering methods.
+    pub fn as_bitmap(&self) -> Bitmap<'_> {
+	Bitmap { bytes: self.get_bytes() }
+    }
+}
+
+impl<'a> From<Bitmap<'a>> for BitmapBuf {
+    fn from(bitmap: Bitmap<'a>) -> BitmapBuf { bitmap.to_owned() }
+}
+
+/// How a `BitmapBuf` is serialized: its width class, and only as many bytes
+/// as it actually uses.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "BitmapBuf")]
+struct BitmapBufRepr {
+    wide: bool,
+    bytes: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl From<BitmapBuf> for BitmapBufRepr {
+    fn from(bitmap: BitmapBuf) -> BitmapBufRepr {
+	BitmapBufRepr { wide: bitmap.wide, bytes: bitmap.get_bytes().to_vec() }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BitmapBufRepr> for BitmapBuf {
+    type Error = &'static str;
+    fn try_from(repr: BitmapBufRepr) -> Result<BitmapBuf, &'static str> {
+	match BitmapBuf::from_bytes(&repr.bytes) {
+	    Some(bitmap) if bitmap.wide == repr.wide => Ok(bitmap),
+	    _ => Err("a narrow bitmap must have 16 bytes, and a wide one 32"),
+	}
+    }
+}
+
+/// The compressed font data that a `Unifont` or `SyncUnifont` reads from.
+#[derive(Clone)]
+enum FontData {
+    /// Data that will be around forever, e.g. the embedded font data.
+    Static(&'static [u8]),
+    /// Data that was loaded at runtime.
+    Shared(Arc<[u8]>),
+    /// Data that is memory-mapped from a file.
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<memmap2::Mmap>),
+}
+
+impl core::ops::Deref for FontData {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+	match self {
+	    FontData::Static(data) => data,
+	    FontData::Shared(data) => data,
+	    #[cfg(feature = "mmap")]
+	    FontData::Mapped(data) => data,
+	}
+    }
+}
+
+impl FontData {
+    /// Reads a whole `.dat` file's worth of font data from the given reader.
+    #[cfg(feature = "std")]
+    fn read_from<R: std::io::Read>(mut reader: R)
+				   -> Result<FontData, UnifontError> {
+	let mut buf = Vec::new();
+	reader.read_to_end(&mut buf)
+	    .map_err(|err| UnifontError::Io(err.kind()))?;
+	Ok(FontData::Shared(buf.into()))
+    }
+    /// Memory-maps the `.dat` file at the given path.
+    #[cfg(feature = "mmap")]
+    fn map_file(path: &std::path::Path) -> Result<FontData, UnifontError> {
+	let file = std::fs::File::open(path)
+	    .map_err(|err| UnifontError::Io(err.kind()))?;
+	// SAFETY: The caller has promised not to modify the file while it's
+	// mapped. (See `Unifont::from_mmap`.)
+	let map = unsafe { memmap2::Mmap::map(&file) }
+	    .map_err(|err| UnifontError::Io(err.kind()))?;
+	Ok(FontData::Mapped(Arc::new(map)))
+    }
 }
 
+/// Where to find a page in the compressed data, and its decompressed data if
+/// it's loaded. `C` is whatever holds the decompressed data: `Option<Vec<u8>>`
+/// for a `Unifont`, or a `OnceBox` for a `SyncUnifont`.
 #[derive(Default)]
-struct PageInfo {
+struct PageInfo<C = Option<Vec<u8>>> {
     uncompressed_size: u32,
     compressed_offset: u32,
-    raw_data: Option<Vec<u8>>,
+    raw_data: C,
+}
+
+impl<C> PageInfo<C> {
+    /// Decompresses this page, and replaces the glyph size markers at the
+    /// beginning of the data with glyph offsets. Only call this if the page
+    /// has any glyphs in it.
+    fn decompress(&self, data: &[u8], codec: Codec)
+		  -> Result<Vec<u8>, UnifontError> {
+	let mut buf = Vec::new();
+	self.decompress_into(data, codec, &mut buf)?;
+	Ok(buf)
+    }
+    /// Like `decompress`, but decompresses into an existing buffer, reusing
+    /// its allocation if it's big enough.
+    fn decompress_into(&self, data: &[u8], codec: Codec, buf: &mut Vec<u8>)
+		       -> Result<(), UnifontError> {
+	if self.uncompressed_size < 512 {
+	    return Err(UnifontError::MalformedPage)
+	}
+	buf.clear();
+	buf.resize(self.uncompressed_size as usize, 0);
+	let input = data
+	    .get(self.compressed_offset as usize ..)
+	    .ok_or(UnifontError::Truncated)?;
+	decompress_exactly(codec, input, &mut buf[..])?;
+	let mut running_offset = 512u16;
+	for n in 0 .. 256 {
+	    let i = (n * 2) as usize;
+	    let in_offset = u16::from_be_bytes(buf[i..i+2].try_into().unwrap());
+	    let out_offset;
+	    match in_offset {
+		0x0000 => {
+		    // narrow char,
+		    out_offset = running_offset;
+		    running_offset += 16;
+		},
+		0x0001 => {
+		    // wide char
+		    out_offset = running_offset | 1;
+		    running_offset += 32;
+		},
+		0x0101 => {
+		    // invalid char
+		    out_offset = 0;
+		},
+		0x0200 ..= 0x02FF => {
+		    // same bitmap as an earlier char in this page
+		    let earlier = (in_offset & 0xFF) as usize * 2;
+		    if earlier >= i {
+			return Err(UnifontError::MalformedPage)
+		    }
+		    out_offset = u16::from_ne_bytes(buf[earlier..earlier+2]
+						    .try_into().unwrap());
+		    if out_offset == 0 {
+			return Err(UnifontError::MalformedPage)
+		    }
+		},
+		_ => {
+		    return Err(UnifontError::MalformedPage)
+		},
+	    }
+	    buf[i..i+2].copy_from_slice(&out_offset.to_ne_bytes());
+	}
+	if running_offset as usize != buf.len() {
+	    return Err(UnifontError::MalformedPage)
+	}
+	Ok(())
+    }
+    /// Returns the decompressed data for this page, and the offset of the
+    /// given codepoint's glyph within that data, iff the page is loaded (i.e.
+    /// `raw_data` is `Some`). An offset of zero means that there's no glyph.
+    /// The low bit of the offset is set if the glyph is wide.
+    fn get_char_offset<'a>(&self, raw_data: Option<&'a [u8]>, codepoint: u32)
+			   -> Option<(&'a [u8], u16)> {
+	if self.uncompressed_size == 0 {
+	    // pages with no glyphs in them don't need to be loaded at all
+	    return Some((&[], 0))
+	}
+	let raw_data = raw_data?;
+	let offset_offset = ((codepoint & 255) as usize) * 2;
+	let char_offset =
+	    u16::from_ne_bytes(raw_data[offset_offset .. offset_offset + 2]
+			       .try_into().unwrap());
+	Some((raw_data, char_offset))
+    }
 }
 
+/// One `PageInfo` for every page in Unicode.
+type PageTable<C> = Box<[PageInfo<C>]>;
+
+/// Reads the header (if any) and the page table from the beginning of the
+/// compressed data.
+fn read_page_table<C: Default>(data: &[u8])
+			       -> Result<(FontHeader, PageTable<C>),
+					 UnifontError> {
+    let (header, table_offset) = FontHeader::parse(data)?;
+    let mut pages: PageTable<C> = (0 .. NUM_UNICODE_PAGES)
+	.map(|_| PageInfo::default()).collect();
+    let start_offset: u32 = data.get(table_offset .. table_offset + 4)
+	.map(BigEndian::read_u32)
+	.ok_or(UnifontError::Truncated)?
+	.checked_add(table_offset as u32 + 4).ok_or(UnifontError::Truncated)?;
+    let mut running_offset = start_offset;
+    let mut buf = [0u8; NUM_UNICODE_PAGES as usize * 4];
+    let compressed_table = data.get(table_offset+4..(running_offset as usize))
+	.ok_or(UnifontError::Truncated)?;
+    decompress_exactly(header.codec, compressed_table, &mut buf)?;
+    for (el, i) in pages.iter_mut().zip(buf.chunks_exact(4)) {
+	let uncompressed_size = BigEndian::read_u16(&i[0..2]);
+	let compressed_size = BigEndian::read_u16(&i[2..4]);
+	el.uncompressed_size = uncompressed_size as u32;
+	if el.uncompressed_size > 0 {
+	    el.compressed_offset = running_offset;
+	    running_offset = running_offset.checked_add(compressed_size as u32)
+		.ok_or(UnifontError::Truncated)?;
+	}
+	else {
+	    el.compressed_offset = 0;
+	}
+    }
+    // Catch truncated data now, rather than when some page that's past the
+    // end fails to decompress.
+    if running_offset as usize > data.len() {
+	return Err(UnifontError::Truncated)
+    }
+    // The checks above catch the most obvious damage, but not corruption
+    // within the pages themselves.
+    #[cfg(feature = "checksum")]
+    if let Some(checksum) = header.checksum {
+	if crc32fast::hash(&data[table_offset ..]) != checksum {
+	    return Err(UnifontError::ChecksumMismatch)
+	}
+    }
+    Ok((header, pages))
+}
+
+/// Something that went wrong while reading the Unifont data. Since the data is
+/// normally embedded in your executable, you should only ever see this if
+/// something went wrong with the build.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum UnifontError {
+    /// The data ended before it was supposed to.
+    Truncated,
+    /// Part of the data failed to decompress.
+    Decompression,
+    /// A page contained an invalid glyph size marker, or its glyph sizes
+    /// didn't add up.
+    MalformedPage,
+    /// The header at the start of the data was malformed.
+    MalformedHeader,
+    /// The data uses a compression format that isn't supported, probably
+    /// because it needs a feature that isn't enabled (see `Codec`).
+    UnsupportedCodec,
+    /// The data didn't match the checksum in its header. Only checked with
+    /// the `checksum` feature.
+    ChecksumMismatch,
+    /// An I/O error occurred while reading the data from a reader.
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+}
+
+impl core::fmt::Display for UnifontError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+	match self {
+	    UnifontError::Truncated
+		=> fmt.write_str("Unifont data is truncated"),
+	    UnifontError::Decompression
+		=> fmt.write_str("Unifont data failed to decompress"),
+	    UnifontError::MalformedPage
+		=> fmt.write_str("Unifont data contains a malformed page"),
+	    UnifontError::MalformedHeader
+		=> fmt.write_str("Unifont data has a malformed header"),
+	    UnifontError::UnsupportedCodec
+		=> fmt.write_str("Unifont data uses an unsupported compression format"),
+	    UnifontError::ChecksumMismatch
+		=> fmt.write_str("Unifont data is corrupted (checksum mismatch)"),
+	    #[cfg(feature = "std")]
+	    UnifontError::Io(kind)
+		=> write!(fmt, "I/O error reading Unifont data: {}", kind),
+	}
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnifontError {}
+
 /// A data structure for caching Unifont character bitmaps. Decompresses the
 /// compressed font data in the executable on demand, and caches it in blocks
 /// ("pages") of 256 code points each.
 pub struct Unifont {
-    pages: [PageInfo; NUM_UNICODE_PAGES as usize],
+    data: FontData,
+    header: FontHeader,
+    /// `None` until the page table is needed, if opened with `open_lazy`.
+    pages: Option<Box<[PageInfo]>>,
+    overrides: BTreeMap<u32, BitmapBuf>,
+    fallback: Option<Box<Unifont>>,
+    replacement: u32,
 }
 
 impl Unifont {
@@ -173,32 +1332,48 @@ impl Unifont {
     /// (if necessary), and returns it.
     ///
     /// Will return the bitmap for U+FFFD REPLACEMENT CHAR (�) if Unifont does
-    /// not include a glyph for this bitmap.
+    /// not include a glyph for this bitmap. (A different replacement can be
+    /// chosen with `set_replacement`.) This is always the case for surrogates
+    /// (U+D800 through U+DFFF), which are recognized without looking at the
+    /// font data.
     ///
     /// **PANICS** if you pass a `codepoint` larger than
     /// `MAX_UNICODE_CODEPOINT`.
-    pub fn load_bitmap(&mut self, codepoint: u32) -> Bitmap {
+    pub fn load_bitmap(&mut self, codepoint: u32) -> Bitmap<'_> {
 	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
-	let page = codepoint >> 8;
-	self.load_page(page);
-	let ret = self.get_bitmap(codepoint);
-	// Justification for this unsafe block:
-	//
-	// Once loaded, the decompressed data for a given page will never be
-	// freed or moved until (and unless) this Unifont instance is dropped.
-	// Therefore, the implied lifetime constraint is met.
-	//
-	// A previous iteration of this API had a "purge_page" call, but that
-	// broke this safety assumption and was therefore removed.
-	if let Some(x) = ret { return unsafe { std::mem::transmute(x) } }
-	drop(ret);
-	if codepoint == 0xFFFD {
-	    panic!("U+FFFD should have been loaded but wasn't!");
-	}
-	else {
-	    // this will happen if U+FFFD was needed but not yet loaded
-	    self.load_bitmap(0xFFFD)
+	self.load_glyph_pages(codepoint);
+	if self.get_bitmap_exact(codepoint) == Some(None) {
+	    // the replacement is needed, and might not be loaded yet
+	    self.load_glyph_pages(self.replacement);
 	}
+	self.get_bitmap(codepoint)
+	    .expect("The replacement glyph should have been loaded but wasn't!")
+    }
+    /// Like `load_bitmap`, but takes a `CodePoint` (or a `char`), so it can't
+    /// panic.
+    pub fn load_codepoint(&mut self, codepoint: impl Into<CodePoint>)
+			  -> Bitmap<'_> {
+	self.load_bitmap(codepoint.into().get())
+    }
+    /// Like `load_bitmap`, but also returns `true` if the bitmap is the
+    /// replacement glyph substituted for a missing one, or `false` if it's the
+    /// codepoint's own glyph. Use this to draw missing glyphs as boxes, while
+    /// still keeping track of what was missing.
+    ///
+    /// Unlike `load_bitmap`, this doesn't panic if you pass a `codepoint`
+    /// larger than `MAX_UNICODE_CODEPOINT`. It returns the substitute instead.
+    pub fn load_bitmap_with_status(&mut self, codepoint: u32)
+				   -> (Bitmap<'_>, bool) {
+	let missing = codepoint > MAX_UNICODE_CODEPOINT
+	    || !self.has_glyph(codepoint);
+	let codepoint = if missing { self.replacement } else { codepoint };
+	(self.load_bitmap(codepoint), missing)
+    }
+    /// Loads the glyph that is substituted for missing glyphs (if necessary),
+    /// and returns it. This is U+FFFD REPLACEMENT CHAR, unless a different
+    /// one was chosen with `set_replacement`.
+    pub fn replacement_bitmap(&mut self) -> Bitmap<'_> {
+	self.load_bitmap(self.replacement)
     }
     /// Gets the Unifont bitmap corresponding to the given Unicode codepoint,
     /// if and only if it is already loaded.
@@ -209,124 +1384,1005 @@ impl Unifont {
     ///
     /// **PANICS** if you pass a `codepoint` larger than
     /// `MAX_UNICODE_CODEPOINT`.
-    pub fn get_bitmap(&self, codepoint: u32) -> Option<Bitmap> {
+    pub fn get_bitmap(&self, codepoint: u32) -> Option<Bitmap<'_>> {
 	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
-	let page = codepoint >> 8;
-	let ch = codepoint & 255;
-	let raw_data = match self.pages[page as usize].raw_data.as_ref() {
-	    None => return None,
-	    Some(x) => &x[..],
-	};
-	let offset_offset = (ch as usize) * 2;
-	let char_offset =
-	    u16::from_ne_bytes(raw_data[offset_offset .. offset_offset + 2]
-			       .try_into().unwrap());
-	if char_offset == 0 {
-	    if codepoint == 0xFFFD {
-		panic!("U+FFFD should have been present but wasn't!");
-	    }
-	    else {
-		self.get_bitmap(0xFFFD)
-	    }
+	match self.get_bitmap_exact(codepoint)? {
+	    Some(bitmap) => Some(bitmap),
+	    // only possible for U+FFFD, in font data that doesn't have it
+	    None if codepoint == self.replacement
+		=> Some(builtin_replacement()),
+	    None => self.get_bitmap(self.replacement),
+	}
+    }
+    /// Like `load_bitmap`, but returns `None` instead of panicking if you
+    /// pass a `codepoint` larger than `MAX_UNICODE_CODEPOINT`. Useful when
+    /// processing untrusted input.
+    pub fn try_load_bitmap(&mut self, codepoint: u32) -> Option<Bitmap<'_>> {
+	if codepoint > MAX_UNICODE_CODEPOINT { return None }
+	Some(self.load_bitmap(codepoint))
+    }
+    /// Like `get_bitmap`, but returns `None` instead of panicking if you pass
+    /// a `codepoint` larger than `MAX_UNICODE_CODEPOINT`.
+    pub fn try_get_bitmap(&self, codepoint: u32) -> Option<Bitmap<'_>> {
+	if codepoint > MAX_UNICODE_CODEPOINT { return None }
+	self.get_bitmap(codepoint)
+    }
+    /// Loads the Unifont bitmap corresponding to the given character (if
+    /// necessary), and returns it. Equivalent to `load_bitmap(c as u32)`, but
+    /// can't panic.
+    pub fn load_char(&mut self, c: char) -> Bitmap<'_> {
+	self.load_bitmap(c as u32)
+    }
+    /// Gets the Unifont bitmap corresponding to the given character, if and
+    /// only if it is already loaded. Equivalent to `get_bitmap(c as u32)`, but
+    /// can't panic.
+    pub fn get_char(&self, c: char) -> Option<Bitmap<'_>> {
+	self.get_bitmap(c as u32)
+    }
+    /// Loads the Unifont bitmap corresponding to the given Unicode codepoint
+    /// (if necessary), and returns it. Unlike `load_bitmap`, returns `None`
+    /// if Unifont does not include a glyph for this codepoint, instead of
+    /// substituting U+FFFD REPLACEMENT CHAR. Useful if you want to fall back
+    /// to another font.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn load_bitmap_exact(&mut self, codepoint: u32) -> Option<Bitmap<'_>> {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	self.load_glyph_pages(codepoint);
+	self.get_bitmap_exact(codepoint).unwrap()
+    }
+    /// Returns the width, in pixels, of the bitmap that `load_bitmap` would
+    /// return for the given codepoint: 8 if it's narrow, 16 if it's wide.
+    ///
+    /// Loads the codepoint's page if necessary, since that's where the widths
+    /// are stored, but once it's loaded, this only reads the glyph's entry
+    /// in the page's offset table, without looking at the bitmap itself.
+    ///
+    /// This comes from the font's own data, not from Unicode's East Asian
+    /// Width property ([UAX #11][1]). The two mostly agree, but not always,
+    /// and a missing glyph gets the width of the replacement glyph. Use this
+    /// when laying out pixels, so that glyphs don't overlap or leave gaps.
+    /// Use `ea_width` instead when you need to line up with a terminal's
+    /// grid.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    ///
+    /// [1]: https://www.unicode.org/reports/tr11/
+    pub fn glyph_width(&mut self, codepoint: u32) -> u32 {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	self.load_glyph_pages(codepoint);
+	let wide = match self.get_glyph_wide(codepoint) {
+	    Some(wide) => wide,
+	    None => {
+		self.load_glyph_pages(self.replacement);
+		// the built-in replacement glyph is narrow
+		self.get_glyph_wide(self.replacement).unwrap_or(false)
+	    },
+	};
+	if wide { 16 } else { 8 }
+    }
+    /// Returns whether the glyph for the given codepoint is wide, or `None`
+    /// if there isn't one. Only reads the wide bit of its offset, so the
+    /// pages that `load_glyph_pages` loads must already be loaded.
+    fn get_glyph_wide(&self, codepoint: u32) -> Option<bool> {
+	if let Some(bitmap) = self.overrides.get(&codepoint) {
+	    return Some(bitmap.is_wide())
+	}
+	if !is_surrogate(codepoint) {
+	    match self.get_char_offset(codepoint) {
+		Some((_, char_offset)) if char_offset != 0
+		    => return Some(char_offset & 1 != 0),
+		_ => (),
+	    }
+	}
+	self.fallback.as_ref()?.get_glyph_wide(codepoint)
+    }
+    /// Returns `true` if `glyph_width` would return 16 for the given
+    /// codepoint, `false` if it would return 8. The same as calling
+    /// `is_wide` on the bitmap that `load_bitmap` would return, but without
+    /// loading the bitmap. See `glyph_width`.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn is_wide(&mut self, codepoint: u32) -> bool {
+	self.glyph_width(codepoint) == 16
+    }
+    /// Returns the total width, in pixels, of the glyphs for every character
+    /// in the given string, laid out side by side. Loads pages as necessary.
+    ///
+    /// Control characters (U+0000 through U+001F, and U+007F through U+009F),
+    /// U+200B ZERO WIDTH SPACE, and U+FEFF ZERO WIDTH NO-BREAK SPACE are
+    /// invisible, and have no width. Like the rest of this crate, this doesn't
+    /// know anything about combining characters. They are measured like any
+    /// other character, by the width of their glyph.
+    pub fn measure_str(&mut self, s: &str) -> u32 {
+	s.chars().map(|c| self.advance(c)).sum()
+    }
+    /// Returns how far to move right after drawing the given character: zero
+    /// if it's invisible (see `measure_str`), otherwise its glyph's width.
+    pub(crate) fn advance(&mut self, c: char) -> u32 {
+	if is_invisible(c) { 0 } else { self.glyph_width(c as u32) }
+    }
+    /// Returns an iterator over the characters of the given string, along with
+    /// the x offset (in pixels) at which to draw each one, and its bitmap.
+    /// The first character is at offset 0, and each following character is
+    /// right after the one before it. Loads every page the string needs up
+    /// front (see `preload_str`).
+    ///
+    /// Invisible characters (see `measure_str`) are skipped, instead of being
+    /// drawn as boxes.
+    pub fn layout_str<'a>(&'a mut self, s: &'a str)
+			  -> impl Iterator<Item = (char, u32, Bitmap<'a>)> + 'a {
+	self.preload_str(s);
+	let this: &'a Unifont = self;
+	let mut x = 0;
+	s.chars().filter(|&c| !is_invisible(c)).map(move |c| {
+	    let bitmap = this.get_char(c)
+		.expect("preload_str should have loaded every page we need!");
+	    let offset = x;
+	    x += bitmap.width();
+	    (c, offset, bitmap)
+	})
+    }
+    /// Returns `true` if Unifont (or an override, or the fallback font) has a
+    /// glyph for the given codepoint, `false` if it doesn't (and
+    /// `load_bitmap` would substitute U+FFFD REPLACEMENT CHAR). Loads the
+    /// codepoint's page if necessary.
+    ///
+    /// Surrogates (U+D800 through U+DFFF) never have glyphs, unless you
+    /// override them, so this returns `false` for them without loading
+    /// anything.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn has_glyph(&mut self, codepoint: u32) -> bool {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	self.load_glyph_pages(codepoint);
+	self.get_has_glyph(codepoint).unwrap()
+    }
+    /// Returns `Some(true)` if Unifont (or an override, or the fallback font)
+    /// has a glyph for the given codepoint, `Some(false)` if it doesn't, or
+    /// `None` if the pages needed to find out aren't loaded yet.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn get_has_glyph(&self, codepoint: u32) -> Option<bool> {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	self.get_bitmap_exact(codepoint).map(|bitmap| bitmap.is_some())
+    }
+    /// Replaces the glyph for the given codepoint with the given bitmap, in
+    /// the same format as `Bitmap::get_bytes`: 16 bytes for a narrow glyph,
+    /// or 32 bytes for a wide one. From then on, every lookup of this
+    /// codepoint returns your bitmap instead of Unifont's, without loading
+    /// its page. Overrides are not affected by `purge_page` or `clear`; use
+    /// `clear_override` to remove one.
+    ///
+    /// Overriding U+FFFD REPLACEMENT CHAR also changes what is substituted
+    /// for missing glyphs.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`, or if `bytes` is not 16 or 32 bytes long.
+    pub fn set_override(&mut self, codepoint: u32, bytes: &[u8]) {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	let bitmap = BitmapBuf::from_bytes(bytes)
+	    .expect("override bitmaps must be 16 or 32 bytes long");
+	self.overrides.insert(codepoint, bitmap);
+    }
+    /// Chooses the codepoint whose glyph is substituted for missing glyphs,
+    /// e.g. U+25A1 WHITE SQUARE, or U+0020 SPACE to just leave a gap. The
+    /// default is U+FFFD REPLACEMENT CHAR. Loads the codepoint's page if
+    /// necessary.
+    ///
+    /// If the glyph later goes away, because it came from an override that
+    /// is cleared with `clear_override` or from a fallback font that is
+    /// replaced with `set_fallback`, the replacement goes back to U+FFFD.
+    ///
+    /// **PANICS** if you pass a `codepoint` that has no glyph (see
+    /// `has_glyph`), or that is larger than `MAX_UNICODE_CODEPOINT`. U+FFFD
+    /// is always accepted (see `REPLACEMENT_BITMAP`).
+    pub fn set_replacement(&mut self, codepoint: u32) {
+	assert!(codepoint == 0xFFFD || self.has_glyph(codepoint),
+		"the replacement codepoint must have a glyph");
+	self.replacement = codepoint;
+    }
+    /// Removes the override for the given codepoint, if there is one, so that
+    /// it goes back to using Unifont's glyph.
+    pub fn clear_override(&mut self, codepoint: u32) {
+	if self.overrides.remove(&codepoint).is_some() {
+	    self.check_replacement();
+	}
+    }
+    /// Goes back to U+FFFD as the replacement, if the one chosen with
+    /// `set_replacement` no longer has a glyph.
+    fn check_replacement(&mut self) {
+	if self.replacement != 0xFFFD && !self.has_glyph(self.replacement) {
+	    self.replacement = 0xFFFD;
+	}
+    }
+    /// Returns the version of Unifont that the font data was compiled from,
+    /// e.g. `"14.0.01"` for the embedded font data, or `None` if the data
+    /// doesn't say.
+    pub fn font_version(&self) -> Option<&str> {
+	self.header.version.as_deref()
+    }
+    /// Returns how many codepoints the font data has glyphs for. Doesn't
+    /// count overrides, or glyphs from the fallback font.
+    ///
+    /// Font data compiled by this version of the crate records this number,
+    /// so it's cheap. For older font data, it has to be counted by
+    /// decompressing every page that isn't already loaded, which takes on the
+    /// order of ten milliseconds.
+    ///
+    /// On an instance opened with `open_lazy`, this reads a temporary copy of
+    /// the page table every time it's called, until the page table is loaded
+    /// for good (by the first lookup, or by `load_page_table`).
+    pub fn glyph_count(&self) -> u32 {
+	if let Some(glyph_count) = self.header.glyph_count {
+	    return glyph_count
+	}
+	self.with_page_table(|pages| {
+	    pages.iter().filter(|page| page.uncompressed_size != 0)
+		.map(|page| match page.raw_data.as_ref() {
+		    Some(raw_data) => count_glyphs(raw_data),
+		    None => count_glyphs(&page.decompress(&self.data,
+							   self.header.codec)
+					 .expect("The Unifont bitmap data in this application appears to be corrupted!")),
+		}).sum()
+	})
+    }
+    /// Returns how many pages of the font data contain at least one glyph.
+    ///
+    /// On an instance opened with `open_lazy`, this reads a temporary copy of
+    /// the page table every time it's called, until the page table is loaded
+    /// for good (by the first lookup, or by `load_page_table`).
+    pub fn covered_pages(&self) -> u32 {
+	self.with_page_table(|pages| {
+	    pages.iter().filter(|page| page.uncompressed_size != 0).count()
+		as u32
+	})
+    }
+    /// Returns an iterator over the numbers (0 through 16) of the Unicode
+    /// planes in which the font data has at least one glyph, in ascending
+    /// order. Like `covered_pages`, this only looks at the page table, so
+    /// it's cheap, with the same caveat about `open_lazy`.
+    pub fn planes(&self) -> impl Iterator<Item = u8> {
+	let mask = self.with_page_table(|pages| {
+	    pages.iter().enumerate()
+		.filter(|(_, page)| page.uncompressed_size != 0)
+		.fold(0u32, |mask, (index, _)| mask | 1 << (index >> 8))
+	});
+	(0 ..= 16u8).filter(move |plane| mask & (1 << plane) != 0)
+    }
+    /// Calls `f` with the page table. If it hasn't been read yet (see
+    /// `open_lazy`), reads a temporary copy.
+    fn with_page_table<R, F: FnOnce(&[PageInfo]) -> R>(&self, f: F) -> R {
+	match self.pages.as_deref() {
+	    Some(pages) => f(pages),
+	    None => f(&read_page_table(&self.data).expect("The Unifont bitmap data in this application appears to be corrupted!").1),
+	}
+    }
+    /// Returns the page table, or an empty slice if it hasn't been read yet.
+    fn pages(&self) -> &[PageInfo] {
+	self.pages.as_deref().unwrap_or(&[])
+    }
+    /// Reads the page table, if it hasn't been read yet. Only does anything
+    /// if this instance was opened with `open_lazy`, in which case the first
+    /// lookup does this anyway. Call it yourself before using methods that
+    /// only take `&self` and need the page table, such as `render_to_buf`, so
+    /// that they don't have to read it again every time.
+    ///
+    /// **PANICS** if the page table turns out to be corrupted. See
+    /// `try_load_page_table`.
+    pub fn load_page_table(&mut self) {
+	self.try_load_page_table().expect("The Unifont bitmap data in this application appears to be corrupted!");
+    }
+    /// Like `load_page_table`, but returns an error instead of panicking if
+    /// the page table is corrupted.
+    pub fn try_load_page_table(&mut self) -> Result<(), UnifontError> {
+	if self.pages.is_none() {
+	    self.pages = Some(read_page_table(&self.data)?.1);
+	}
+	Ok(())
+    }
+    /// Returns an iterator over every codepoint that the font data has a
+    /// glyph for, in ascending order. Like `glyph_count`, this doesn't
+    /// include overrides, or glyphs from the fallback font.
+    ///
+    /// Pages are loaded as the iterator reaches them, so consuming the whole
+    /// thing is about as expensive as `preload_all`, and leaves every page
+    /// loaded. Use `clear` afterwards if you don't need them.
+    pub fn covered_codepoints(&mut self) -> impl Iterator<Item = u32> + '_ {
+	self.load_page_table();
+	(0 ..= MAX_UNICODE_PAGE)
+	    .filter(|&page| self.pages()[page as usize].uncompressed_size != 0)
+	    .collect::<Vec<u32>>().into_iter()
+	    .flat_map(move |page| {
+		self.load_page(page);
+		(page << 8 .. (page << 8) + 256)
+		    .filter(|&codepoint| matches!(self.get_char_offset(codepoint),
+						  Some((_, offset)) if offset != 0))
+		    .collect::<Vec<u32>>()
+	    })
+    }
+    /// Counts the glyphs in the given inclusive range of codepoints, e.g.
+    /// `block_coverage(0x0400, 0x04FF)` for the Cyrillic block. Returns
+    /// `(present, total)`, where `present` is how many codepoints in the
+    /// range `has_glyph` would return `true` for, and `total` is how many
+    /// codepoints are in the range. Loads every page that overlaps the range.
+    ///
+    /// An `end` larger than `MAX_UNICODE_CODEPOINT` is clamped. If `start` is
+    /// larger than `end`, returns `(0, 0)`.
+    pub fn block_coverage(&mut self, start: u32, end: u32) -> (u32, u32) {
+	let end = end.min(MAX_UNICODE_CODEPOINT);
+	if start > end { return (0, 0) }
+	let present = (start ..= end)
+	    .filter(|&codepoint| self.has_glyph(codepoint))
+	    .count() as u32;
+	(present, end - start + 1)
+    }
+    /// Sets a fallback font, which will be consulted for any codepoint that
+    /// this one doesn't have a glyph for, before substituting U+FFFD
+    /// REPLACEMENT CHAR. Use this to layer a supplemental font (created with
+    /// e.g. `Unifont::from_bytes`) on top of this one. The fallback can have
+    /// its own fallback, and so on. Replaces any previous fallback.
+    pub fn set_fallback(&mut self, other: Unifont) {
+	self.fallback = Some(Box::new(other));
+	self.check_replacement();
+    }
+    /// Looks up the glyph for the given codepoint, without substituting
+    /// U+FFFD. Returns `None` if the pages needed to find out aren't loaded,
+    /// or `Some(None)` if there's no glyph.
+    fn get_bitmap_exact(&self, codepoint: u32) -> Option<Option<Bitmap<'_>>> {
+	if let Some(bitmap) = self.overrides.get(&codepoint) {
+	    return Some(Some(bitmap.as_bitmap()))
+	}
+	if is_surrogate(codepoint) { return Some(None) }
+	let (raw_data, char_offset) = self.get_char_offset(codepoint)?;
+	if char_offset != 0 {
+	    Some(Some(bitmap_at(raw_data, char_offset)))
+	}
+	else if let Some(fallback) = self.fallback.as_ref() {
+	    fallback.get_bitmap_exact(codepoint)
+	}
+	else {
+	    Some(None)
+	}
+    }
+    /// Loads every page that `get_bitmap_exact` needs for the given
+    /// codepoint: the codepoint's page, and, if this font doesn't have a
+    /// glyph for it, the fallback's.
+    fn load_glyph_pages(&mut self, codepoint: u32) {
+	if self.overrides.contains_key(&codepoint) || is_surrogate(codepoint) {
+	    return
+	}
+	self.load_page(codepoint >> 8);
+	let missing = matches!(self.get_char_offset(codepoint), Some((_, 0)));
+	if missing {
+	    if let Some(fallback) = self.fallback.as_mut() {
+		fallback.load_glyph_pages(codepoint);
+	    }
+	}
+    }
+    /// Copies the bytes of the glyph for the given codepoint into `buf`, in
+    /// the same format as `Bitmap::get_bytes`, without loading or caching its
+    /// page. The page is decompressed into `buf` itself, which is then
+    /// truncated to just the glyph, so reusing the same `buf` for every call
+    /// avoids allocating. Use this if you only need one glyph at a time, and
+    /// don't want to keep whole pages around.
+    ///
+    /// Overrides and the fallback font are respected. Unlike `load_bitmap`,
+    /// U+FFFD REPLACEMENT CHAR is not substituted.
+    ///
+    /// Returns `true` if there was a glyph, or `false` (leaving `buf` empty)
+    /// if there wasn't. Either way, nothing is cached, so this works the same
+    /// on an instance opened with `open_lazy` whose page table hasn't been
+    /// read yet, but then every call has to read a temporary copy of the
+    /// page table first, roughly doubling its cost. Call `load_page_table`
+    /// once beforehand to avoid that.
+    ///
+    /// If the page is already loaded, its glyph is just copied. Otherwise,
+    /// this decompresses the whole page every time, so it's much slower than
+    /// the caching path for repeated lookups. The same goes for each
+    /// fallback font that has to be consulted.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`, or if the font data is corrupted.
+    pub fn render_to_buf(&self, codepoint: u32, buf: &mut Vec<u8>) -> bool {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	buf.clear();
+	if let Some(bitmap) = self.overrides.get(&codepoint) {
+	    buf.extend_from_slice(bitmap.get_bytes());
+	    return true
+	}
+	// Only look at our own page here. If it's loaded and doesn't have the
+	// glyph, we go straight to the fallback instead of decompressing it
+	// again.
+	let found = if is_surrogate(codepoint) { false }
+	else if let Some((raw_data, char_offset))
+	    = self.get_char_offset(codepoint) {
+	    if char_offset != 0 {
+		let bitmap = bitmap_at(raw_data, char_offset);
+		buf.extend_from_slice(bitmap.get_bytes());
+	    }
+	    char_offset != 0
 	}
 	else {
-	    let is_wide = (char_offset & 1) != 0;
-	    let real_offset = (char_offset & !1) as usize;
-	    let region = &raw_data[real_offset .. real_offset +
-				   if is_wide { 32 } else { 16 }];
-	    Some(Bitmap { bytes: region })
+	    self.with_page_table(|pages| {
+		let page_info = &pages[(codepoint >> 8) as usize];
+		page_info.decompress_into(&self.data, self.header.codec, buf)
+		    .expect("The Unifont bitmap data in this application appears to be corrupted!");
+		match page_info.get_char_offset(Some(buf), codepoint) {
+		    Some((_, char_offset)) if char_offset != 0 => {
+			let start = (char_offset & !1) as usize;
+			let len = if char_offset & 1 != 0 { 32 } else { 16 };
+			buf.copy_within(start .. start + len, 0);
+			buf.truncate(len);
+			true
+		    },
+		    _ => { buf.clear(); false },
+		}
+	    })
+	};
+	if found { return true }
+	match self.fallback.as_ref() {
+	    Some(fallback) => fallback.render_to_buf(codepoint, buf),
+	    None => false,
+	}
+    }
+    /// Draws every glyph in the given page into a "contact sheet": a 16x16
+    /// grid of 16x16 cells, with U+xx00 in the upper left, U+xx0F in the upper
+    /// right, and U+xxFF in the lower right. Every pixel is scaled up to a
+    /// `scale` by `scale` square, so the image is `256 * scale` pixels on each
+    /// side. Returns the image as 8-bit grayscale, row by row; inked pixels
+    /// are `0xFF`, all other pixels are `0x00`.
+    ///
+    /// Narrow glyphs are drawn in the left half of their cell. Codepoints
+    /// with no glyph are left blank, rather than drawn as the replacement
+    /// glyph, so that gaps in coverage stand out. Loads the page if
+    /// necessary.
+    ///
+    /// **PANICS** if you pass a `page` larger than `MAX_UNICODE_PAGE`, or a
+    /// `scale` of zero.
+    pub fn render_page_sheet(&mut self, page: u32, scale: u32) -> Vec<u8> {
+	assert!(page <= MAX_UNICODE_PAGE);
+	assert!(scale >= 1);
+	let pitch = (256 * scale) as usize;
+	let mut ret = vec![0; pitch * pitch];
+	for index in 0 .. 256 {
+	    let Some(bitmap) = self.load_bitmap_exact(page << 8 | index)
+	    else { continue };
+	    let cell_x = (index % 16 * 16 * scale) as usize;
+	    let cell_y = (index / 16 * 16 * scale) as usize;
+	    for (x, y) in bitmap.iter_set_pixels() {
+		let left = cell_x + x as usize * scale as usize;
+		let top = cell_y + y as usize * scale as usize;
+		for row in top .. top + scale as usize {
+		    ret[row * pitch + left .. row * pitch + left
+			+ scale as usize].fill(0xFF);
+		}
+	    }
 	}
+	ret
+    }
+    /// Returns the decompressed data for the given codepoint's page, and the
+    /// offset of its glyph within that data, iff the page is loaded.
+    fn get_char_offset(&self, codepoint: u32) -> Option<(&[u8], u16)> {
+	let page_info = self.pages().get((codepoint >> 8) as usize)?;
+	page_info.get_char_offset(page_info.raw_data.as_deref(), codepoint)
     }
     /// Loads a given page, if it's not loaded already. (Since loading is
     /// usually done transparently, this isn't usually needed.)
+    ///
+    /// **PANICS** if the font data for this page turns out to be corrupted.
+    /// See `try_load_page`.
     pub fn load_page(&mut self, page: u32) {
+	self.try_load_page(page).expect("The Unifont bitmap data in this application appears to be corrupted!")
+    }
+    /// Loads a given page, if it's not loaded already. Returns an error if the
+    /// font data for this page turns out to be corrupted.
+    ///
+    /// **PANICS** if you pass a `page` larger than `MAX_UNICODE_PAGE`.
+    pub fn try_load_page(&mut self, page: u32) -> Result<(), UnifontError> {
 	assert!(page <= MAX_UNICODE_PAGE);
-	let target_page = &mut self.pages[page as usize];
-	if target_page.raw_data.is_none() {
-	    if target_page.uncompressed_size == 0 {
-		target_page.raw_data = Some(vec![0u8; 512]);
+	self.try_load_page_table()?;
+	let target_page = &mut self.pages.as_mut().unwrap()[page as usize];
+	if target_page.raw_data.is_none() && target_page.uncompressed_size != 0 {
+	    target_page.raw_data = Some(target_page.decompress(&self.data,
+							       self.header.codec)?);
+	}
+	Ok(())
+    }
+    /// Loads every page needed to display the given string, including the
+    /// page containing U+FFFD REPLACEMENT CHAR if any of its characters are
+    /// missing from Unifont. Afterwards, `get_char` and `get_bitmap` are
+    /// guaranteed to succeed for every character in the string, without
+    /// needing `mut`.
+    pub fn preload_str(&mut self, s: &str) {
+	for c in s.chars() {
+	    if !self.has_glyph(c as u32) {
+		self.load_glyph_pages(self.replacement);
 	    }
-	    else {
-		let mut inflater = flate2::Decompress::new(true);
-		let mut buf = vec![0; target_page.uncompressed_size as usize];
-		inflater.decompress(&UNIFONT_DATA[target_page.compressed_offset as usize ..], &mut buf[..], flate2::FlushDecompress::Finish).expect("The Unifont bitmap data in this application appears to be corrupted!");
-		let mut running_offset = 512u16;
-		for n in 0 .. 256 {
-		    let i = (n * 2) as usize;
-		    let in_offset = u16::from_be_bytes(buf[i..i+2].try_into().unwrap());
-		    let out_offset;
-		    match in_offset {
-			0x0000 => {
-			    // narrow char,
-			    out_offset = running_offset;
-			    running_offset += 16;
-			},
-			0x0001 => {
-			    // wide char
-			    out_offset = running_offset | 1;
-			    running_offset += 32;
-			},
-			0x0101 => {
-			    // invalid char
-			    out_offset = 0;
-			},
-			_ => {
-			    panic!("The Unifont bitmap data in this application appears to be corrupted!");
-			},
-		    }
-		    buf[i..i+2].copy_from_slice(&out_offset.to_ne_bytes());
-		}
-		target_page.raw_data = Some(buf)
+	}
+    }
+    /// Loads every page that overlaps the given inclusive range of
+    /// codepoints, as well as the page containing U+FFFD REPLACEMENT CHAR.
+    /// Afterwards, `get_bitmap` is guaranteed to succeed for every codepoint
+    /// in the range, without needing `mut`.
+    ///
+    /// An `end` larger than `MAX_UNICODE_CODEPOINT` is clamped. If `start` is
+    /// larger than `end`, only the U+FFFD page is loaded.
+    pub fn preload_range(&mut self, start: u32, end: u32) {
+	let end = end.min(MAX_UNICODE_CODEPOINT);
+	if start <= end {
+	    for page in (start >> 8) ..= (end >> 8) {
+		self.load_page(page);
 	    }
 	}
+	self.load_glyph_pages(self.replacement);
+	if let Some(fallback) = self.fallback.as_mut() {
+	    fallback.preload_range(start, end);
+	}
+    }
+    /// Loads every page that contains any glyphs. Afterwards, `get_bitmap` is
+    /// guaranteed to succeed for every codepoint, without needing `mut`, so
+    /// this instance can be shared between threads without any further
+    /// synchronization.
+    ///
+    /// This adds about 2.3 megabytes of memory overhead, and takes on the
+    /// order of ten milliseconds in an optimized build.
+    pub fn preload_all(&mut self) {
+	for page in 0 ..= MAX_UNICODE_PAGE {
+	    self.load_page(page);
+	}
+	if let Some(fallback) = self.fallback.as_mut() {
+	    fallback.preload_all();
+	}
+    }
+    /// Loads every page (see `preload_all`) and returns a `FrozenUnifont`,
+    /// which can look up any glyph without needing `mut`, and can be shared
+    /// between threads.
+    pub fn into_frozen(mut self) -> FrozenUnifont {
+	self.preload_all();
+	FrozenUnifont { inner: self }
+    }
+    /// Frees the decompressed data for a given page, if it's loaded. It will
+    /// be decompressed again the next time it's needed. Long-running
+    /// applications can use this to reclaim memory from pages they no longer
+    /// need.
+    ///
+    /// Any `Bitmap` you got from this instance borrows it, so the borrow
+    /// checker will make sure that none of them are still around when you
+    /// call this.
+    ///
+    /// **PANICS** if you pass a `page` larger than `MAX_UNICODE_PAGE`.
+    pub fn purge_page(&mut self, page: u32) {
+	assert!(page <= MAX_UNICODE_PAGE);
+	if let Some(pages) = self.pages.as_mut() {
+	    pages[page as usize].raw_data = None;
+	}
+	if let Some(fallback) = self.fallback.as_mut() {
+	    fallback.purge_page(page);
+	}
+    }
+    /// Frees the decompressed data for every loaded page, returning this
+    /// instance to the state it was in when it was first opened. Like
+    /// `purge_page`, this requires `&mut self`, so no `Bitmap`s can still be
+    /// around when you call it.
+    pub fn clear(&mut self) {
+	for page in self.pages.iter_mut().flat_map(|pages| pages.iter_mut()) {
+	    page.raw_data = None;
+	}
+	if let Some(fallback) = self.fallback.as_mut() {
+	    fallback.clear();
+	}
+    }
+    /// Returns `true` if the given page is currently loaded. Pages that
+    /// contain no glyphs never need to be loaded, and are never reported as
+    /// loaded.
+    ///
+    /// **PANICS** if you pass a `page` larger than `MAX_UNICODE_PAGE`.
+    pub fn is_page_loaded(&self, page: u32) -> bool {
+	assert!(page <= MAX_UNICODE_PAGE);
+	self.pages().get(page as usize)
+	    .is_some_and(|page| page.raw_data.is_some())
+    }
+    /// Returns an iterator over the numbers of all currently loaded pages, in
+    /// ascending order.
+    pub fn loaded_pages(&self) -> impl Iterator<Item = u32> + '_ {
+	self.pages().iter().enumerate()
+	    .filter(|(_, page)| page.raw_data.is_some())
+	    .map(|(n, _)| n as u32)
+    }
+    /// Returns the number of bytes of decompressed page data currently cached
+    /// by this instance. Doesn't count the fixed overhead of the instance
+    /// itself.
+    pub fn memory_usage(&self) -> usize {
+	self.pages().iter()
+	    .filter_map(|page| page.raw_data.as_ref())
+	    .map(|raw_data| raw_data.len())
+	    .sum()
     }
     /// Creates a new instance of this class, with no glyphs cached yet.
     ///
     /// The font data is embedded in your executable, and does not need to be
     /// provided any other way.
+    ///
+    /// **PANICS** if the embedded font data is corrupted. See `try_open`.
+    ///
+    /// Only available with the `embed-data` feature (enabled by default).
+    #[cfg(feature = "embed-data")]
     pub fn open() -> Unifont {
-	// oh boy, this pain point hasn't been resolved yet
-	let mut pages: [std::mem::MaybeUninit<PageInfo>;
-			NUM_UNICODE_PAGES as usize]
-	    = unsafe { std::mem::MaybeUninit::uninit().assume_init() };
-	for el in &mut pages[..] {
-	    unsafe { std::ptr::write(el.as_mut_ptr(), PageInfo {
-		compressed_offset: 0, uncompressed_size: 0, raw_data: None
-	    }) }
-	}
-	let mut ret = Unifont { pages: unsafe { std::mem::transmute(pages) } };
-	ret.populate_page_infos();
-	ret
+	Unifont::try_open().expect("The Unifont bitmap data in this application appears to be corrupted!")
     }
-    fn populate_page_infos(&mut self) {
-	let mut input = UNIFONT_DATA;
-	let start_offset: u32
-	    = input.read_u32::<BigEndian>().unwrap() + 4;
-	let mut running_offset = start_offset;
-	let mut buf = [0u8; NUM_UNICODE_PAGES as usize * 4];
-	let mut fish = flate2::Decompress::new(true);
-	fish.decompress(&UNIFONT_DATA[4..(running_offset as usize)],
-			&mut buf, flate2::FlushDecompress::Finish).unwrap();
-	let mut i = &buf[..];
-	for el in &mut self.pages[..] {
-	    let uncompressed_size = i.read_u16::<BigEndian>().unwrap();
-	    let compressed_size = i.read_u16::<BigEndian>().unwrap();
-	    el.uncompressed_size = uncompressed_size as u32;
-	    if el.uncompressed_size > 0 {
-		el.compressed_offset = running_offset;
-		running_offset += compressed_size as u32;
-	    }
-	    else {
-		el.compressed_offset = 0;
-	    }
+    /// Creates a new instance of this class, with no glyphs cached yet.
+    /// Returns an error if the embedded font data is corrupted, instead of
+    /// panicking.
+    ///
+    /// Only the page table is checked up front. Corruption within a page
+    /// won't be detected until that page is loaded; use `try_load_page` if you
+    /// need to handle that gracefully too.
+    ///
+    /// Only available with the `embed-data` feature (enabled by default).
+    #[cfg(feature = "embed-data")]
+    pub fn try_open() -> Result<Unifont, UnifontError> {
+	Unifont::from_data(FontData::Static(UNIFONT_DATA))
+    }
+    /// Like `open`, but doesn't read the page table until it's first needed,
+    /// e.g. by the first `load_bitmap`. This makes opening cheaper, at the
+    /// cost of making the first lookup slower by the same amount, so it only
+    /// helps programs that might not look up any glyphs at all.
+    ///
+    /// Until something reads the page table, `get_bitmap`, `try_get_bitmap`,
+    /// `get_char`, and `get_has_glyph` return `None` for every codepoint
+    /// that doesn't have an override, just as if no pages were loaded. The
+    /// `load_*` methods read it as needed, and `load_page_table` reads it
+    /// without looking anything up.
+    ///
+    /// **PANICS** if the embedded font data is corrupted, but possibly not
+    /// until the page table is read.
+    ///
+    /// Only available with the `embed-data` feature (enabled by default).
+    #[cfg(feature = "embed-data")]
+    pub fn open_lazy() -> Unifont {
+	let data = FontData::Static(UNIFONT_DATA);
+	let (header, _) = FontHeader::parse(&data).expect("The Unifont bitmap data in this application appears to be corrupted!");
+	Unifont {
+	    data, header,
+	    pages: None,
+	    overrides: BTreeMap::new(),
+	    fallback: None,
+	    replacement: 0xFFFD,
+	}
+    }
+    /// Creates a new instance of this class, reading from the given font
+    /// data instead of the embedded font data. The data must be in the same
+    /// format as the `unifont.dat` file that comes with this crate, which is
+    /// produced by the `compile-font` tool. Use this to embed your own font,
+    /// e.g. `Unifont::with_data(include_bytes!("myfont.dat"))`.
+    ///
+    /// Unlike `from_bytes`, this does not copy the data.
+    pub fn with_data(data: &'static [u8]) -> Result<Unifont, UnifontError> {
+	Unifont::from_data(FontData::Static(data))
+    }
+    /// Creates a new instance of this class, reading from a copy of the given
+    /// font data instead of the embedded font data. The data must be in the
+    /// same format as the `unifont.dat` file that comes with this crate,
+    /// which is produced by the `compile-font` tool.
+    ///
+    /// As with `try_open`, only the page table is checked up front.
+    pub fn from_bytes(data: &[u8]) -> Result<Unifont, UnifontError> {
+	Unifont::from_data(FontData::Shared(data.into()))
+    }
+    /// Creates a new instance of this class, reading all of the font data
+    /// from the given reader. Equivalent to reading everything into a buffer
+    /// and calling `from_bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(reader: R)
+					 -> Result<Unifont, UnifontError> {
+	Unifont::from_data(FontData::read_from(reader)?)
+    }
+    /// Creates a new instance of this class, memory-mapping the font data
+    /// from the given `.dat` file instead of reading it onto the heap. Only
+    /// the page table is read up front; the rest of the file is only paged
+    /// in as pages are decompressed, and processes that map the same file
+    /// share its memory.
+    ///
+    /// The file must not be modified or truncated while any `Unifont` (or
+    /// clone of one) created from it is still alive. If it is, you may get
+    /// garbage glyphs, panics, or worse.
+    ///
+    /// Only available with the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<std::path::Path>>(path: P)
+						-> Result<Unifont, UnifontError> {
+	Unifont::from_data(FontData::map_file(path.as_ref())?)
+    }
+    fn from_data(data: FontData) -> Result<Unifont, UnifontError> {
+	let (header, pages) = read_page_table(&data)?;
+	Ok(Unifont {
+	    data, header,
+	    pages: Some(pages),
+	    overrides: BTreeMap::new(),
+	    fallback: None,
+	    replacement: 0xFFFD,
+	})
+    }
+}
+
+/// Equivalent to `Unifont::open()`.
+#[cfg(feature = "embed-data")]
+impl Default for Unifont {
+    fn default() -> Unifont { Unifont::open() }
+}
+
+/// Cloning a `Unifont` is cheap: it copies the page table, but not the cache.
+/// The clone starts out with no pages loaded, just as if it had been freshly
+/// opened, but without the cost of decompressing the page table again. This
+/// is useful for giving each worker thread its own cache.
+impl Clone for Unifont {
+    fn clone(&self) -> Unifont {
+	let pages = self.pages.as_ref().map(|pages| {
+	    pages.iter().map(|page| PageInfo {
+		uncompressed_size: page.uncompressed_size,
+		compressed_offset: page.compressed_offset,
+		raw_data: None,
+	    }).collect()
+	});
+	Unifont {
+	    data: self.data.clone(),
+	    header: self.header.clone(),
+	    pages,
+	    overrides: self.overrides.clone(),
+	    fallback: self.fallback.clone(),
+	    replacement: self.replacement,
 	}
     }
 }
 
-#[cfg(test)]
+/// A `Unifont` that has had every page loaded, and can no longer be changed.
+/// Lookups never fail and never need `mut`, and it's `Send` and `Sync`, so a
+/// single instance can be shared between any number of rendering threads.
+/// Create one with `Unifont::into_frozen`.
+pub struct FrozenUnifont {
+    inner: Unifont,
+}
+
+impl FrozenUnifont {
+    /// Returns the Unifont bitmap corresponding to the given Unicode
+    /// codepoint.
+    ///
+    /// Will return the bitmap for U+FFFD REPLACEMENT CHAR (�) if Unifont does
+    /// not include a glyph for this bitmap.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`.
+    pub fn get_bitmap(&self, codepoint: u32) -> Bitmap<'_> {
+	self.inner.get_bitmap(codepoint)
+	    .expect("FrozenUnifont should have every page loaded!")
+    }
+    /// Returns the Unifont bitmap corresponding to the given character.
+    /// Equivalent to `get_bitmap(c as u32)`, but can't panic.
+    pub fn get_char(&self, c: char) -> Bitmap<'_> {
+	self.get_bitmap(c as u32)
+    }
+}
+
+/// A thread-safe alternative to `Unifont`, for when you can't afford to load
+/// every page up front like `FrozenUnifont` does. Pages are still loaded on
+/// demand, but loading only needs `&self`, so a single instance can be shared
+/// between threads (e.g. in an `Arc`, or a `static`).
+///
+/// Each page can only be loaded once, and is never freed until the whole
+/// instance is dropped. If two threads need the same unloaded page at the
+/// same time, they may both decompress it, but only one copy is kept.
+pub struct SyncUnifont {
+    data: FontData,
+    header: FontHeader,
+    pages: Box<[PageInfo<OnceBox<Vec<u8>>>]>,
+}
+
+impl SyncUnifont {
+    /// Loads the Unifont bitmap corresponding to the given Unicode codepoint
+    /// (if necessary), and returns it.
+    ///
+    /// Will return the bitmap for U+FFFD REPLACEMENT CHAR (�) if Unifont does
+    /// not include a glyph for this bitmap.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`, or if the font data for its page turns out to
+    /// be corrupted.
+    pub fn load_bitmap(&self, codepoint: u32) -> Bitmap<'_> {
+	self.load_bitmap_exact(codepoint).unwrap_or_else(|| {
+	    if codepoint == 0xFFFD { builtin_replacement() }
+	    else { self.load_bitmap(0xFFFD) }
+	})
+    }
+    /// Loads the Unifont bitmap corresponding to the given Unicode codepoint
+    /// (if necessary), and returns it. Returns `None` if Unifont does not
+    /// include a glyph for this codepoint.
+    ///
+    /// **PANICS** if you pass a `codepoint` larger than
+    /// `MAX_UNICODE_CODEPOINT`, or if the font data for its page turns out to
+    /// be corrupted.
+    pub fn load_bitmap_exact(&self, codepoint: u32) -> Option<Bitmap<'_>> {
+	assert!(codepoint <= MAX_UNICODE_CODEPOINT);
+	if is_surrogate(codepoint) { return None }
+	let page_info = &self.pages[(codepoint >> 8) as usize];
+	let raw_data = if page_info.uncompressed_size == 0 { None } else {
+	    Some(&page_info.raw_data.get_or_init(|| {
+		Box::new(page_info.decompress(&self.data, self.header.codec).expect("The Unifont bitmap data in this application appears to be corrupted!"))
+	    })[..])
+	};
+	match page_info.get_char_offset(raw_data, codepoint) {
+	    Some((raw_data, char_offset)) if char_offset != 0
+		=> Some(bitmap_at(raw_data, char_offset)),
+	    _ => None,
+	}
+    }
+    /// Loads the Unifont bitmap corresponding to the given character (if
+    /// necessary), and returns it. Equivalent to `load_bitmap(c as u32)`, but
+    /// can't panic on range.
+    pub fn load_char(&self, c: char) -> Bitmap<'_> {
+	self.load_bitmap(c as u32)
+    }
+    /// Creates a new instance of this class, with no glyphs cached yet.
+    ///
+    /// **PANICS** if the embedded font data is corrupted. See `try_open`.
+    ///
+    /// Only available with the `embed-data` feature (enabled by default).
+    #[cfg(feature = "embed-data")]
+    pub fn open() -> SyncUnifont {
+	SyncUnifont::try_open().expect("The Unifont bitmap data in this application appears to be corrupted!")
+    }
+    /// Creates a new instance of this class, with no glyphs cached yet.
+    /// Returns an error if the embedded font data is corrupted, instead of
+    /// panicking.
+    ///
+    /// Only available with the `embed-data` feature (enabled by default).
+    #[cfg(feature = "embed-data")]
+    pub fn try_open() -> Result<SyncUnifont, UnifontError> {
+	SyncUnifont::from_data(FontData::Static(UNIFONT_DATA))
+    }
+    /// Creates a new instance of this class, reading from the given font
+    /// data instead of the embedded font data. See `Unifont::with_data`.
+    pub fn with_data(data: &'static [u8])
+		     -> Result<SyncUnifont, UnifontError> {
+	SyncUnifont::from_data(FontData::Static(data))
+    }
+    /// Creates a new instance of this class, reading from a copy of the given
+    /// font data instead of the embedded font data. See
+    /// `Unifont::from_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<SyncUnifont, UnifontError> {
+	SyncUnifont::from_data(FontData::Shared(data.into()))
+    }
+    /// Creates a new instance of this class, reading all of the font data
+    /// from the given reader. See `Unifont::from_reader`.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(reader: R)
+					 -> Result<SyncUnifont, UnifontError> {
+	SyncUnifont::from_data(FontData::read_from(reader)?)
+    }
+    /// Creates a new instance of this class, memory-mapping the font data
+    /// from the given `.dat` file. See `Unifont::from_mmap`, including its
+    /// warning about modifying the file.
+    ///
+    /// Only available with the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<std::path::Path>>(path: P)
+					    -> Result<SyncUnifont, UnifontError> {
+	SyncUnifont::from_data(FontData::map_file(path.as_ref())?)
+    }
+    /// Returns the version of Unifont that the font data was compiled from.
+    /// See `Unifont::font_version`.
+    pub fn font_version(&self) -> Option<&str> {
+	self.header.version.as_deref()
+    }
+    fn from_data(data: FontData) -> Result<SyncUnifont, UnifontError> {
+	let (header, pages) = read_page_table(&data)?;
+	Ok(SyncUnifont { data, header, pages })
+    }
+}
+
+/// Returns a shared, lazily-initialized `SyncUnifont` instance, for programs
+/// that just want to look up glyphs without passing a `Unifont` around. It's
+/// opened the first time this is called, and lives for the rest of the
+/// program, so the bitmaps it returns are `'static`.
+///
+/// ```rust
+/// let bitmap = unifont_bitmap::global().load_char('井');
+/// assert!(bitmap.is_wide());
+/// ```
+///
+/// Only available with the `global` feature.
+#[cfg(feature = "global")]
+pub fn global() -> &'static SyncUnifont {
+    static GLOBAL: OnceBox<SyncUnifont> = OnceBox::new();
+    GLOBAL.get_or_init(|| Box::new(SyncUnifont::open()))
+}
+
+/// Decompresses data in the given format that should exactly fill `out`.
+fn decompress_exactly(codec: Codec, input: &[u8], out: &mut [u8])
+		      -> Result<(), UnifontError> {
+    match codec {
+	Codec::Zlib => inflate_exactly(input, out),
+	#[cfg(feature = "zstd")]
+	Codec::Zstd => unzstd_exactly(input, out),
+    }
+}
+
+/// Decompresses a zstd frame that should exactly fill `out`.
+#[cfg(feature = "zstd")]
+fn unzstd_exactly(input: &[u8], out: &mut [u8]) -> Result<(), UnifontError> {
+    use std::io::Read;
+    let mut decoder = zstd::stream::read::Decoder::with_buffer(input)
+	.map_err(|_| UnifontError::Decompression)?
+	.single_frame();
+    decoder.read_exact(out).map_err(|_| UnifontError::Decompression)?;
+    match decoder.read(&mut [0]) {
+	Ok(0) => Ok(()),
+	_ => Err(UnifontError::Decompression),
+    }
+}
+
+/// Decompresses a zlib stream that should exactly fill `out`.
+fn inflate_exactly(input: &[u8], out: &mut [u8]) -> Result<(), UnifontError> {
+    use miniz_oxide::inflate::{core::{decompress, inflate_flags::*,
+				      DecompressorOxide},
+			       TINFLStatus};
+    let mut inflater: Box<DecompressorOxide> = Box::default();
+    let flags = TINFL_FLAG_PARSE_ZLIB_HEADER | TINFL_FLAG_COMPUTE_ADLER32
+	| TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+    match decompress(&mut inflater, input, out, 0, flags) {
+	(TINFLStatus::Done, _, written) if written == out.len() => Ok(()),
+	_ => Err(UnifontError::Decompression),
+    }
+}
+
+/// Returns `true` if the given character should take up no space when laying
+/// out text: the C0 and C1 control characters, U+200B ZERO WIDTH SPACE, and
+/// U+FEFF ZERO WIDTH NO-BREAK SPACE.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\0' ..= '\x1F' | '\x7F' ..= '\u{9F}' | '\u{200B}' | '\u{FEFF}')
+}
+
+/// Returns the glyph to use when the font data doesn't even have U+FFFD
+/// REPLACEMENT CHAR, e.g. because it was made by a `FontBuilder` that was
+/// never given one.
+fn builtin_replacement() -> Bitmap<'static> {
+    Bitmap { bytes: &ascii::REPLACEMENT_TABLE }
+}
+
+/// Returns `true` if the given codepoint is a surrogate (U+D800 through
+/// U+DFFF). Surrogates only exist to encode other codepoints in UTF-16, so
+/// they can never have glyphs of their own.
+pub(crate) fn is_surrogate(codepoint: u32) -> bool {
+    (0xD800 ..= 0xDFFF).contains(&codepoint)
+}
+
+/// Counts the glyphs in a decompressed page, i.e. its non-zero offsets.
+fn count_glyphs(raw_data: &[u8]) -> u32 {
+    raw_data[..512].chunks_exact(2)
+	.filter(|offset| offset != &[0, 0])
+	.count() as u32
+}
+
+/// Returns the bitmap at the given (nonzero) offset within a page's
+/// decompressed data.
+fn bitmap_at(raw_data: &[u8], char_offset: u16) -> Bitmap<'_> {
+    let is_wide = (char_offset & 1) != 0;
+    let real_offset = (char_offset & !1) as usize;
+    let region = &raw_data[real_offset .. real_offset +
+			   if is_wide { 32 } else { 16 }];
+    Bitmap { bytes: region }
+}
+
+#[cfg(all(test, feature = "std", feature = "embed-data"))]
 mod test {
     use super::*;
     #[test]
+    #[allow(dropping_copy_types)]
     fn bogus_page() {
 	let mut unifont = Unifont::open();
 	let fffd = unifont.load_bitmap(0xFFFD);
@@ -337,4 +2393,1091 @@ mod test {
 	let bad = unifont.get_bitmap(0x104560);
 	assert_eq!(fffd, bad);
     }
+    #[test]
+    fn default_is_open() {
+	let mut default = Unifont::default();
+	let mut open = Unifont::open();
+	assert_eq!(default.font_version(), open.font_version());
+	assert_eq!(default.covered_pages(), open.covered_pages());
+	assert_eq!(default.memory_usage(), 0);
+	for codepoint in ['A' as u32, '井' as u32, 0x1F600, 0x10FFFF] {
+	    assert_eq!(default.load_bitmap(codepoint).to_owned(),
+		       open.load_bitmap(codepoint).to_owned());
+	}
+    }
+    #[test]
+    fn external_data() {
+	let mut unifont = Unifont::from_reader(UNIFONT_DATA).unwrap();
+	let mut embedded = Unifont::open();
+	assert_eq!(unifont.load_char('井'), embedded.load_char('井'));
+	assert_eq!(Unifont::from_bytes(&UNIFONT_DATA[..2]).err(),
+		   Some(UnifontError::Truncated));
+	let mut unifont = Unifont::with_data(UNIFONT_DATA).unwrap();
+	assert_eq!(unifont.load_char('A'), embedded.load_char('A'));
+	let sync = SyncUnifont::from_bytes(UNIFONT_DATA).unwrap();
+	assert_eq!(sync.load_char('A'), embedded.load_char('A'));
+    }
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mapped_data() {
+	let path = std::env::temp_dir()
+	    .join(format!("unifont-bitmap-test-{}.dat", std::process::id()));
+	std::fs::write(&path, UNIFONT_DATA).unwrap();
+	let mut unifont = Unifont::from_mmap(&path).unwrap();
+	let mut embedded = Unifont::open();
+	assert_eq!(unifont.load_char('井'), embedded.load_char('井'));
+	drop(unifont);
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(Unifont::from_mmap(&path).err(),
+		   Some(UnifontError::Io(std::io::ErrorKind::NotFound)));
+    }
+    #[test]
+    fn overrides() {
+	let mut unifont = Unifont::open();
+	let mut smiley = [0u8; 32];
+	smiley[4] = 0x24;
+	smiley[10] = 0x42;
+	smiley[11] = 0x3C;
+	unifont.set_override('A' as u32, &smiley[..16]);
+	unifont.set_override(0x104560, &smiley);
+	assert_eq!(unifont.get_char('A').unwrap().get_bytes(), &smiley[..16]);
+	assert!(!unifont.is_page_loaded(0));
+	unifont.clear();
+	assert_eq!(unifont.load_bitmap(0x104560).get_bytes(), &smiley[..]);
+	assert_eq!(unifont.glyph_width(0x104560), 16);
+	assert!(unifont.has_glyph(0x104560));
+	unifont.clear_override('A' as u32);
+	assert_eq!(unifont.load_char('A').to_hex_line('A' as u32),
+		   "0041:0000000018242442427E424242420000");
+	assert_eq!(BitmapBuf::from_bytes(&smiley[..20]), None);
+    }
+    #[test]
+    fn fallback() {
+	let mut supplement = Unifont::open();
+	supplement.set_override(0x104560, &[0x55; 16]);
+	let mut unifont = Unifont::open();
+	let fffd = unifont.load_bitmap(0xFFFD).to_owned();
+	assert!(!unifont.has_glyph(0x104560));
+	unifont.set_fallback(supplement);
+	assert!(unifont.has_glyph(0x104560));
+	assert_eq!(unifont.load_bitmap(0x104560).get_bytes(), &[0x55; 16]);
+	assert_eq!(unifont.load_bitmap(0x104561).to_owned(), fffd);
+	unifont.preload_range(0x104500, 0x1045FF);
+	let frozen = unifont.into_frozen();
+	assert_eq!(frozen.get_bitmap(0x104560).get_bytes(), &[0x55; 16]);
+    }
+    #[test]
+    fn compile_hex() {
+	let hex = "0041:0000000018242442427E424242420000\r\n\
+		   not a glyph\n\
+		   1F600:000003E00C181004200226324631400140014FF92AAA26B2\
+		   13E40C1803E00000\n\
+		   0041:00000000000000000000000000000000\n";
+	let mut dat = Vec::new();
+	let stats = compile_from_hex(hex.as_bytes(), &mut dat).unwrap();
+	assert_eq!(stats.bitmaps, 2);
+	assert_eq!(stats.bitmap_bytes, 48);
+	assert_eq!(stats.pages, 2);
+	assert_eq!(stats.skipped_lines, 1);
+	let mut unifont = Unifont::from_bytes(&dat).unwrap();
+	assert_eq!(unifont.load_bitmap(0x1F600).to_hex_line(0x1F600),
+		   "01F600:000003E00C181004200226324631400140014FF92AAA26B2\
+		    13E40C1803E00000");
+	assert_eq!(unifont.load_char('A').get_bytes(), &[0; 16]);
+	assert!(!unifont.has_glyph('B' as u32));
+	assert!(!unifont.has_glyph(0x4E95));
+    }
+    #[test]
+    fn font_builder() {
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16])
+	    .add_wide(0xE000, [0x81; 32])
+	    .add_narrow(0xE001, [0x18; 16])
+	    .add_narrow(0xE002, [0x18; 16]);
+	assert_eq!(builder.len(), 4);
+	let mut unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.load_bitmap(0xE000).get_bytes(), &[0x81; 32]);
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), &[0x18; 16]);
+	assert_eq!(unifont.load_bitmap(0xE002).get_bytes(), &[0x18; 16]);
+	assert_eq!(unifont.memory_usage(), 512 + 32 + 16 + 16);
+	assert_eq!(unifont.load_char('A').get_bytes(), &[0xFF; 16]);
+	assert!(!unifont.has_glyph('A' as u32));
+	// with dedup, the duplicate glyph is only stored once
+	builder.set_dedup(true);
+	let mut unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.load_bitmap(0xE002).get_bytes(), &[0x18; 16]);
+	assert_eq!(unifont.memory_usage(), 512 + 32 + 16);
+    }
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn zstd_codec() {
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16])
+	    .add_wide(0xE000, [0x81; 32])
+	    .set_codec(Codec::Zstd);
+	let mut unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.load_bitmap(0xE000).get_bytes(), &[0x81; 32]);
+	assert_eq!(unifont.load_char('A').get_bytes(), &[0xFF; 16]);
+	assert_eq!(unifont.glyph_count(), 2);
+    }
+    #[test]
+    fn font_version() {
+	assert_eq!(Unifont::open().font_version(), Some("14.0.01"));
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]);
+	let unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.font_version(), None);
+	builder.set_version("1.2.3");
+	let mut unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.font_version(), Some("1.2.3"));
+	assert_eq!(unifont.load_char('A').get_bytes(), &[0xFF; 16]);
+	let mut dat = builder.finish();
+	dat[12] = 0xFF;
+	assert_eq!(Unifont::from_bytes(&dat).err(),
+		   Some(UnifontError::MalformedHeader));
+    }
+    /// How many glyphs, and pages with glyphs, the embedded data has.
+    #[cfg(not(feature = "bmp-only"))]
+    const EMBEDDED_COUNTS: (u32, u32) = (70369, 428);
+    #[cfg(feature = "bmp-only")]
+    const EMBEDDED_COUNTS: (u32, u32) = (57086, 223);
+    #[test]
+    fn glyph_counts() {
+	let unifont = Unifont::open();
+	assert_eq!(unifont.glyph_count(), EMBEDDED_COUNTS.0);
+	assert_eq!(unifont.covered_pages(), EMBEDDED_COUNTS.1);
+	// same data, minus the header with the precomputed count
+	let table_offset = u32::from_be_bytes(UNIFONT_DATA[4..8].try_into()
+					      .unwrap()) as usize + 8;
+	let mut unifont = Unifont::from_bytes(&UNIFONT_DATA[table_offset..])
+	    .unwrap();
+	unifont.load_char('A');
+	assert_eq!(unifont.glyph_count(), EMBEDDED_COUNTS.0);
+	let mut covered = unifont.covered_codepoints();
+	assert_eq!(covered.next(), Some(0));
+	assert_eq!(covered.count() as u32, EMBEDDED_COUNTS.0 - 1);
+	#[cfg(not(feature = "bmp-only"))]
+	assert!(unifont.is_page_loaded(0x1F6));
+	assert_eq!(unifont.block_coverage(0x0400, 0x04FF), (256, 256));
+	assert_eq!(unifont.block_coverage(0x104500, 0x1045FF), (0, 256));
+	assert_eq!(unifont.block_coverage(0x10FFFF, 0xFFFFFFFF).1, 1);
+	assert_eq!(unifont.block_coverage(2, 1), (0, 0));
+    }
+    #[test]
+    fn checksum() {
+	let (header, _) = FontHeader::parse(UNIFONT_DATA).unwrap();
+	assert!(header.checksum.is_some());
+	let mut corrupted = UNIFONT_DATA.to_vec();
+	*corrupted.last_mut().unwrap() ^= 0x01;
+	#[cfg(feature = "checksum")]
+	assert_eq!(Unifont::from_bytes(&corrupted).err(),
+		   Some(UnifontError::ChecksumMismatch));
+	#[cfg(not(feature = "checksum"))]
+	assert!(Unifont::from_bytes(&corrupted).is_ok());
+	// data without a checksum is still accepted
+	let table_offset = u32::from_be_bytes(UNIFONT_DATA[4..8].try_into()
+					      .unwrap()) as usize + 8;
+	assert!(Unifont::from_bytes(&UNIFONT_DATA[table_offset..]).is_ok());
+    }
+    #[test]
+    fn page_sheet() {
+	let mut unifont = Unifont::open();
+	let sheet = unifont.render_page_sheet(0, 2);
+	assert_eq!(sheet.len(), 512 * 512);
+	// 'A' is U+0041: row 4, column 1
+	let a = unifont.load_char('A').to_owned();
+	for y in 0 .. 32 {
+	    for x in 0 .. 32 {
+		let expected = a.as_bitmap().get_pixel(x / 2, y / 2);
+		let pixel = sheet[((4 * 32 + y) * 512 + 32 + x) as usize];
+		assert_eq!(pixel == 0xFF, expected);
+	    }
+	}
+	// missing glyphs are left blank
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]);
+	builder.add_narrow(0x0000, [0xFF; 16]);
+	let mut unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	let sheet = unifont.render_page_sheet(0, 1);
+	assert_eq!(sheet.len(), 256 * 256);
+	for (index, &pixel) in sheet.iter().enumerate() {
+	    let (x, y) = (index % 256, index / 256);
+	    assert_eq!(pixel == 0xFF, x < 8 && y < 16);
+	}
+    }
+    #[test]
+    fn blit_mono() {
+	let mut unifont = Unifont::open();
+	let bitmap = unifont.load_char('今');
+	for x in [0, 3, 8, 13, 21, 30] {
+	    // 4 bytes (32 pixels) wide, 20 rows tall
+	    let mut dest = [0u8; 4 * 20];
+	    bitmap.blit_mono(&mut dest, 4, x, 2);
+	    let mut expected = [0u8; 32 * 20];
+	    bitmap.blit_alpha(&mut expected, 32, x, 2);
+	    for (index, &alpha) in expected.iter().enumerate() {
+		let bit = dest[index / 8] & (0x80 >> (index % 8)) != 0;
+		assert_eq!(bit, alpha != 0, "x = {}, pixel {}", x, index);
+	    }
+	}
+	// clipped at the bottom, and off the right edge entirely
+	let mut dest = [0u8; 4 * 4];
+	bitmap.blit_mono(&mut dest, 4, 40, 0);
+	bitmap.blit_mono(&mut dest, 4, 0, 10);
+	assert!(dest.iter().all(|&x| x == 0));
+	bitmap.blit_mono(&mut dest, 4, 0, 2);
+	assert_eq!(&dest[8..10], &bitmap.get_bytes()[..2]);
+	// existing pixels are kept
+	let mut dest = [0x01u8; 2 * 16];
+	unifont.load_char(' ').blit_mono(&mut dest, 2, 5, 0);
+	assert!(dest.iter().all(|&x| x == 0x01));
+    }
+    #[test]
+    #[cfg(feature = "ansi")]
+    fn render_ansi() {
+	let mut unifont = Unifont::open();
+	let red = (255, 0, 0);
+	let black = (0, 0, 0);
+	let space = unifont.load_char(' ').render_ansi(red, black);
+	assert_eq!(space.lines().next().unwrap(),
+		   "\x1B[38;2;0;0;0;48;2;0;0;0m▀▀▀▀▀▀▀▀\x1B[0m");
+	for c in ['A', '今'] {
+	    let bitmap = unifont.load_char(c);
+	    let ansi = bitmap.render_ansi(red, black);
+	    assert_eq!(ansi.lines().count(), 8);
+	    for line in ansi.lines() {
+		assert_eq!(line.matches('▀').count() as u32, bitmap.width());
+		assert!(line.ends_with("\x1B[0m"));
+	    }
+	    assert!(ansi.contains("38;2;255;0;0"));
+	}
+    }
+    #[test]
+    fn planes() {
+	let unifont = Unifont::open_lazy();
+	#[cfg(not(feature = "bmp-only"))]
+	assert_eq!(unifont.planes().collect::<Vec<_>>(), [0, 1, 2, 14]);
+	#[cfg(feature = "bmp-only")]
+	assert_eq!(unifont.planes().collect::<Vec<_>>(), [0]);
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]);
+	builder.add_narrow(0x10FFFF, [0xFF; 16]);
+	let unifont = Unifont::from_bytes(&builder.finish()).unwrap();
+	assert_eq!(unifont.planes().collect::<Vec<_>>(), [0, 16]);
+    }
+    #[test]
+    #[cfg(feature = "bmp-only")]
+    fn bmp_only() {
+	let mut unifont = Unifont::open();
+	assert!(unifont.has_glyph(0xFFFD));
+	assert!(!unifont.has_glyph(0x1F600));
+	assert!(unifont.load_bitmap_with_status(0x1F600).1);
+	assert_eq!(unifont.covered_codepoints().last(), Some(0xFFFD));
+    }
+    #[test]
+    fn truncated_data() {
+	let truncated = &UNIFONT_DATA[.. UNIFONT_DATA.len() - 1];
+	assert_eq!(Unifont::from_bytes(truncated).err(),
+		   Some(UnifontError::Truncated));
+	assert_eq!(SyncUnifont::from_bytes(truncated).err(),
+		   Some(UnifontError::Truncated));
+	assert_eq!(Unifont::from_bytes(&[]).err(), Some(UnifontError::Truncated));
+    }
+    #[test]
+    fn lazy_open() {
+	let mut unifont = Unifont::open_lazy();
+	assert_eq!(unifont.font_version(), Some("14.0.01"));
+	assert_eq!(unifont.covered_pages(), EMBEDDED_COUNTS.1);
+	assert_eq!(unifont.get_char('A'), None);
+	for codepoint in [0, 0xFFFD, 0x1F600, 0x10FFFF] {
+	    assert!(unifont.get_bitmap(codepoint).is_none());
+	    assert!(unifont.try_get_bitmap(codepoint).is_none());
+	}
+	let mut overridden = Unifont::open_lazy();
+	overridden.set_override('A' as u32, &[0xFF; 16]);
+	assert!(overridden.get_char('A').is_some());
+	assert!(!unifont.is_page_loaded(0));
+	assert_eq!(unifont.memory_usage(), 0);
+	let clone = unifont.clone();
+	unifont.clear();
+	assert_eq!(unifont.load_char('A').to_owned(),
+		   Unifont::open().load_char('A').to_owned());
+	assert!(unifont.is_page_loaded(0));
+	assert!(clone.get_has_glyph(0x104560).is_none());
+	// loading just the page table, for render_to_buf's sake
+	let mut lazy = Unifont::open_lazy();
+	lazy.load_page_table();
+	assert!(!lazy.is_page_loaded(0));
+	assert_eq!(lazy.get_has_glyph('A' as u32), None);
+	let mut buf = Vec::new();
+	assert!(lazy.render_to_buf('A' as u32, &mut buf));
+	assert_eq!(&buf[..], ascii_bitmap(b'A'));
+	assert_eq!(lazy.memory_usage(), 0);
+    }
+    #[test]
+    fn render_to_buf() {
+	let unifont = Unifont::open();
+	let mut cached = Unifont::open();
+	let mut buf = Vec::new();
+	let astral = if cfg!(feature = "bmp-only") { 0x263A } else { 0x1F600 };
+	for codepoint in ['A' as u32, '井' as u32, astral] {
+	    assert!(unifont.render_to_buf(codepoint, &mut buf));
+	    assert_eq!(&buf[..], cached.load_bitmap(codepoint).get_bytes());
+	}
+	assert!(!unifont.render_to_buf(0x104560, &mut buf));
+	assert!(buf.is_empty());
+	assert_eq!(unifont.memory_usage(), 0);
+	cached.set_override('A' as u32, &[0x55; 16]);
+	assert!(cached.render_to_buf('A' as u32, &mut buf));
+	assert_eq!(&buf[..], &[0x55; 16]);
+	// a loaded page is copied from, and isn't disturbed
+	let usage = cached.memory_usage();
+	assert!(cached.render_to_buf('井' as u32, &mut buf));
+	assert_eq!(&buf[..], cached.get_char('井').unwrap().get_bytes());
+	assert!(!cached.render_to_buf(0x1F6D8, &mut buf));
+	assert!(buf.is_empty());
+	assert_eq!(cached.memory_usage(), usage);
+	// fallback fonts, with their pages and ours loaded or not
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]).add_narrow(0xE000, [0x11; 16]);
+	let main_dat = builder.finish();
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]).add_narrow(0xE001, [0x22; 16]);
+	let fallback_dat = builder.finish();
+	for (main_loaded, fallback_loaded) in [(false, false), (true, false),
+					       (false, true), (true, true)] {
+	    let mut main = Unifont::from_bytes(&main_dat).unwrap();
+	    let mut fallback = Unifont::from_bytes(&fallback_dat).unwrap();
+	    if main_loaded { main.load_page(0xE0) }
+	    if fallback_loaded { fallback.load_page(0xE0) }
+	    main.set_fallback(fallback);
+	    assert!(main.render_to_buf(0xE000, &mut buf));
+	    assert_eq!(&buf[..], &[0x11; 16]);
+	    assert!(main.render_to_buf(0xE001, &mut buf));
+	    assert_eq!(&buf[..], &[0x22; 16]);
+	    assert!(!main.render_to_buf(0xE002, &mut buf));
+	    assert!(buf.is_empty());
+	    assert!(!main.render_to_buf(0xD800, &mut buf));
+	}
+    }
+    #[test]
+    fn render_halfblock() {
+	let mut unifont = Unifont::open();
+	let halfblock = unifont.load_char('A').render_halfblock();
+	assert_eq!(halfblock.lines().count(), 8);
+	assert_eq!(halfblock, ["        ",
+			       "        ",
+			       "  ▄▀▀▄  ",
+			       " ▄▀  ▀▄ ",
+			       " █▄▄▄▄█ ",
+			       " █    █ ",
+			       " █    █ ",
+			       "        "].join("\n"));
+	let halfblock = unifont.load_char('井').render_halfblock();
+	assert_eq!(halfblock.lines().count(), 8);
+	assert!(halfblock.lines().all(|line| line.chars().count() == 16));
+    }
+    #[test]
+    fn render_braille() {
+	let mut unifont = Unifont::open();
+	// between them, the cells of the second line use all eight dots: '⢀' is
+	// dot 8, '⠎' is dots 2, 3, and 4, '⠱' is 1, 5, and 6, and '⡀' is 7
+	assert_eq!(unifont.load_char('A').render_braille(),
+		   ["⠀⠀⠀⠀",
+		    "⢀⠎⠱⡀",
+		    "⢸⠒⠒⡇",
+		    "⠘⠀⠀⠃"].join("\n"));
+	assert_eq!(unifont.load_char('井').render_braille(),
+		   ["⠀⠀⡇⠀⠀⡇⠀⠀",
+		    "⠈⠉⡏⠉⠉⡏⠉⠀",
+		    "⠒⠒⡗⠒⠒⡗⠒⠂",
+		    "⢀⠜⠀⠀⠀⡇⠀⠀"].join("\n"));
+	// one pixel at a time, to pin down each dot's number
+	let dots = [((0, 0), '⠁'), ((0, 1), '⠂'), ((0, 2), '⠄'), ((1, 0), '⠈'),
+		    ((1, 1), '⠐'), ((1, 2), '⠠'), ((0, 3), '⡀'), ((1, 3), '⢀')];
+	for ((x, y), dot) in dots {
+	    let mut bytes = [0; 16];
+	    bytes[y] = 0x80 >> x;
+	    let braille = Bitmap { bytes: &bytes }.render_braille();
+	    assert_eq!(braille.chars().next(), Some(dot));
+	    assert_eq!(braille.chars().filter(|&c| c != '⠀' && c != '\n')
+		       .count(), 1);
+	}
+    }
+    #[test]
+    fn pixels_match_bytes() {
+	let mut unifont = Unifont::open();
+	for codepoint in ['A' as u32, '井' as u32] {
+	    let bitmap = unifont.load_bitmap(codepoint);
+	    let (width, height) = bitmap.get_dimensions::<u32>();
+	    let pitch = width / 8;
+	    for y in 0 .. height {
+		for x in 0 .. width {
+		    let byte = bitmap.get_bytes()[(y * pitch + x / 8) as usize];
+		    assert_eq!(bitmap.get_pixel(x, y),
+			       (byte << (x % 8)) & 0x80 != 0);
+		}
+	    }
+	    assert!(!bitmap.get_pixel(width, 0));
+	    assert!(!bitmap.get_pixel(0, height));
+	    let art = bitmap.to_string();
+	    assert_eq!(art.lines().count(), 16);
+	    assert!(art.lines().all(|line| line.len() == width as usize));
+	    let grid = bitmap.to_bool_grid();
+	    for y in 0 .. 16 {
+		for x in 0 .. 16 {
+		    assert_eq!(grid[y as usize][x as usize],
+			       bitmap.get_pixel(x, y));
+		    assert_eq!(grid[y as usize][x as usize],
+			       bitmap.get_row(y) & (0x8000 >> x) != 0);
+		}
+	    }
+	}
+    }
+    #[test]
+    fn index_pixel() {
+	let mut unifont = Unifont::open();
+	for codepoint in ['A' as u32, '井' as u32] {
+	    let bitmap = unifont.load_bitmap(codepoint);
+	    let (width, height) = bitmap.get_dimensions::<u32>();
+	    for y in 0 .. height {
+		for x in 0 .. width {
+		    assert_eq!(bitmap[(x, y)], bitmap.get_pixel(x, y));
+		}
+	    }
+	}
+    }
+    #[test]
+    #[should_panic]
+    fn index_pixel_out_of_bounds() {
+	let mut unifont = Unifont::open();
+	let _ = unifont.load_char('A')[(8, 0)];
+    }
+    #[test]
+    fn scale_nearest() {
+	let mut unifont = Unifont::open();
+	for codepoint in ['A' as u32, '井' as u32] {
+	    let bitmap = unifont.load_bitmap(codepoint);
+	    let width = bitmap.width();
+	    assert_eq!(bitmap.scale_nearest(1), bitmap.to_bool_grid()
+		       .iter().flat_map(|row| row[.. width as usize].iter())
+		       .copied().collect::<Vec<_>>());
+	    let scaled = bitmap.scale_nearest(2);
+	    assert_eq!(scaled.len() as u32, width * 2 * 32);
+	    for y in 0 .. 32 {
+		for x in 0 .. width * 2 {
+		    assert_eq!(scaled[(y * width * 2 + x) as usize],
+			       bitmap.get_pixel(x / 2, y / 2));
+		}
+	    }
+	}
+    }
+    #[test]
+    #[should_panic]
+    fn scale_nearest_zero() {
+	let mut unifont = Unifont::open();
+	unifont.load_char('A').scale_nearest(0);
+    }
+    #[test]
+    fn to_pbm() {
+	let mut unifont = Unifont::open();
+	let mut expected = b"P4\n8 16\n".to_vec();
+	expected.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x18, 0x24, 0x24,
+				     0x42, 0x42, 0x7E, 0x42, 0x42, 0x42, 0x42,
+				     0x00, 0x00]);
+	assert_eq!(unifont.load_char('A').to_pbm(), expected);
+	// wide rows are two bytes each, left half first
+	let pbm = unifont.load_char('井').to_pbm();
+	assert!(pbm.starts_with(b"P4\n16 16\n"));
+	assert_eq!(pbm.len(), 9 + 32);
+	assert_eq!(&pbm[9 .. 13], &[0x08, 0x20, 0x08, 0x20]);
+	assert_eq!(&pbm[9 + 18 .. 9 + 20], &[0xFF, 0xFE]);
+    }
+    #[test]
+    fn try_out_of_range() {
+	let mut unifont = Unifont::open();
+	unifont.load_bitmap(MAX_UNICODE_CODEPOINT);
+	for codepoint in [MAX_UNICODE_CODEPOINT + 1, u32::MAX] {
+	    assert!(unifont.try_get_bitmap(codepoint).is_none());
+	    assert!(unifont.try_load_bitmap(codepoint).is_none());
+	}
+	assert!(unifont.try_get_bitmap(MAX_UNICODE_CODEPOINT).is_some());
+	assert!(unifont.try_load_bitmap(MAX_UNICODE_CODEPOINT).is_some());
+    }
+    #[test]
+    fn preload_all() {
+	let mut unifont = Unifont::open();
+	unifont.preload_all();
+	let mut fresh = Unifont::open();
+	// plane 0, 1, 2, 14, an empty plane, and a surrogate. Under bmp-only,
+	// everything past plane 0 is the replacement glyph, but still `Some`.
+	for codepoint in ['A' as u32, '今' as u32, 0xFFFD, 0x1F600, 0x20000,
+			  0xE0100, 0x50000, 0x10FFFF, 0xD800] {
+	    assert_eq!(unifont.get_bitmap(codepoint).map(|x| x.to_owned()),
+		       Some(fresh.load_bitmap(codepoint).to_owned()),
+		       "U+{:04X}", codepoint);
+	}
+	#[cfg(not(feature = "bmp-only"))]
+	assert!(unifont.get_has_glyph(0x1F600).unwrap());
+	#[cfg(feature = "bmp-only")]
+	assert!(!unifont.get_has_glyph(0x1F600).unwrap());
+    }
+    #[test]
+    fn measure_str() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.measure_str(""), 0);
+	assert_eq!(unifont.measure_str("Hello"), 40);
+	assert_eq!(unifont.measure_str("A今B"), 32);
+	assert_eq!(unifont.measure_str("\u{FEFF}a\tb\u{200B}c\r\n\u{85}"), 24);
+    }
+    #[test]
+    #[cfg(feature = "layout")]
+    fn wrap_str() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.wrap_str("the quick  brown fox", 80),
+		   ["the quick", "brown fox"]);
+	assert_eq!(unifont.wrap_str("a abcdefghijk b", 40),
+		   ["a", "abcde", "fghij", "k b"]);
+	assert_eq!(unifont.wrap_str("今日は\n\nhi", 32), ["今日", "は", "", "hi"]);
+	assert_eq!(unifont.wrap_str("", 80), [""]);
+    }
+    #[test]
+    fn layout_str() {
+	let mut unifont = Unifont::open();
+	let laid_out: Vec<(char, u32, BitmapBuf)> = unifont.layout_str("A今B")
+	    .map(|(c, x, bitmap)| (c, x, bitmap.to_owned())).collect();
+	assert_eq!(laid_out.iter().map(|&(c, x, _)| (c, x)).collect::<Vec<_>>(),
+		   [('A', 0), ('今', 8), ('B', 24)]);
+	assert_eq!(unifont.layout_str("a\tb").map(|(c, x, _)| (c, x))
+		   .collect::<Vec<_>>(), [('a', 0), ('b', 8)]);
+	assert_eq!(laid_out[1].2, unifont.load_char('今').to_owned());
+    }
+    #[test]
+    fn mirror_pairs() {
+	for (a, b) in [('(', ')'), ('<', '>'), ('«', '»'), ('≤', '≥'),
+		       ('∈', '∋'), ('「', '」'), ('∟', '⯾'), ('∠', '⦣'),
+		       ('∡', '⦛'), ('≅', '≌'), ('⪃', '⪄'), ('⪪', '⪫')] {
+	    assert_eq!(mirror_codepoint(a as u32), b as u32);
+	    assert_eq!(mirror_codepoint(b as u32), a as u32);
+	}
+	// best-fit mirrors aren't used
+	for c in ['A', '∑', '√', '今', '∕', '⧵', '⊨', '⫤'] {
+	    assert_eq!(mirror_codepoint(c as u32), c as u32);
+	}
+	assert!(mirror::MIRROR_PAIRS.windows(2).all(|x| x[0].0 < x[1].0));
+    }
+    #[test]
+    fn bitmap_status() {
+	let mut unifont = Unifont::open();
+	let replacement = unifont.load_bitmap(0xFFFD).to_owned();
+	let (bitmap, missing) = unifont.load_bitmap_with_status('A' as u32);
+	assert_eq!((bitmap.get_bytes(), missing), (ascii_bitmap(b'A'), false));
+	for codepoint in [0xE000, 0x110000, u32::MAX] {
+	    let (bitmap, missing) = unifont.load_bitmap_with_status(codepoint);
+	    assert_eq!((bitmap.to_owned(), missing), (replacement, true));
+	}
+	// U+FFFD itself is not a substitution
+	assert!(!unifont.load_bitmap_with_status(0xFFFD).1);
+    }
+    #[test]
+    fn replacement() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.replacement_bitmap().get_bytes(), REPLACEMENT_BITMAP);
+	let square = unifont.load_bitmap(0x25A1).to_owned();
+	unifont.set_replacement(0x25A1);
+	assert_eq!(unifont.load_bitmap(0xE000).to_owned(), square);
+	assert_eq!(unifont.load_bitmap_with_status(0xE000), (square.as_bitmap(), true));
+	assert_eq!(unifont.replacement_bitmap(), square.as_bitmap());
+	let mut unifont = Unifont::open();
+	unifont.set_replacement(' ' as u32);
+	unifont.preload_str("\u{E000}");
+	assert!(unifont.get_bitmap(0xE000).unwrap().is_blank());
+	assert!(std::panic::catch_unwind(move || unifont.set_replacement(0xE000))
+		.is_err());
+	// a replacement that only exists as an override
+	let mut unifont = Unifont::open();
+	unifont.set_override(0xE000, &[0xAA; 16]);
+	unifont.set_replacement(0xE000);
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), &[0xAA; 16]);
+	unifont.clear_override(0xE000);
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), REPLACEMENT_BITMAP);
+	// or only in the fallback font
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xFFFD, [0xFF; 16]).add_narrow(0xE000, [0x55; 16]);
+	let mut unifont = Unifont::open();
+	unifont.set_fallback(Unifont::from_bytes(&builder.finish()).unwrap());
+	unifont.set_replacement(0xE000);
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), &[0x55; 16]);
+	builder.retain(|codepoint| codepoint == 0xFFFD);
+	unifont.set_fallback(Unifont::from_bytes(&builder.finish()).unwrap());
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), REPLACEMENT_BITMAP);
+    }
+    #[test]
+    fn no_replacement_glyph() {
+	let mut builder = FontBuilder::new();
+	builder.add_narrow(0xE000, [0xAA; 16]);
+	let dat = builder.finish();
+	let mut unifont = Unifont::from_bytes(&dat).unwrap();
+	assert!(!unifont.has_glyph(0xFFFD));
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), REPLACEMENT_BITMAP);
+	assert_eq!(unifont.load_bitmap(0xFFFD).get_bytes(), REPLACEMENT_BITMAP);
+	assert_eq!(unifont.load_bitmap_with_status('A' as u32),
+		   (Bitmap { bytes: &REPLACEMENT_BITMAP }, true));
+	assert_eq!(unifont.get_bitmap(0xE000).unwrap().get_bytes(), &[0xAA; 16]);
+	unifont.set_replacement(0xE000);
+	assert_eq!(unifont.load_bitmap(0xE001).get_bytes(), &[0xAA; 16]);
+	unifont.set_replacement(0xFFFD);
+	assert_eq!(unifont.replacement_bitmap().get_bytes(), REPLACEMENT_BITMAP);
+	let sync = SyncUnifont::from_bytes(&dat).unwrap();
+	assert_eq!(sync.load_bitmap(0xE001).get_bytes(), REPLACEMENT_BITMAP);
+	assert_eq!(sync.load_bitmap(0xFFFD).get_bytes(), REPLACEMENT_BITMAP);
+    }
+    #[test]
+    fn codepoints() {
+	assert_eq!(CodePoint::try_from(0x10FFFF).map(u32::from), Ok(0x10FFFF));
+	assert_eq!(CodePoint::try_from(0x110000), Err(InvalidCodePoint(0x110000)));
+	let codepoint = CodePoint::from('ÿ');
+	assert_eq!((codepoint.get(), codepoint.page()), (0xFF, 0));
+	assert_eq!(codepoint.to_string(), "U+00FF");
+	let mut unifont = Unifont::open();
+	let expected = unifont.load_char('ÿ').to_owned();
+	assert_eq!(unifont.load_codepoint(codepoint), expected.as_bitmap());
+	assert_eq!(unifont.load_codepoint('ÿ'), expected.as_bitmap());
+    }
+    #[test]
+    fn glyph_width() {
+	let mut unifont = Unifont::open();
+	for codepoint in ['A' as u32, '今' as u32, 0x1F6D8, 0xD800, 0xE0080] {
+	    let width = unifont.load_bitmap(codepoint).width();
+	    assert_eq!(unifont.glyph_width(codepoint), width);
+	}
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.glyph_width('今' as u32), 16);
+	assert_eq!(unifont.glyph_width(0x1F6D8), 8);
+	unifont.set_override('A' as u32, &[0xFF; 32]);
+	assert_eq!(unifont.glyph_width('A' as u32), 16);
+    }
+    #[test]
+    fn is_wide() {
+	let mut unifont = Unifont::open();
+	assert!(!unifont.is_wide('A' as u32));
+	assert!(unifont.is_wide('今' as u32));
+	assert_eq!(unifont.is_wide(0xE0080), unifont.is_wide(0xFFFD));
+    }
+    #[test]
+    fn east_asian_width() {
+	assert_eq!(ea_width('A' as u32), 1);
+	assert_eq!(ea_width('今' as u32), 2);
+	assert_eq!(ea_width('Ａ' as u32), 2);
+	assert_eq!(ea_width('ｱ' as u32), 1);
+	assert_eq!(ea_width(0x1F600), 2);
+	assert_eq!(ea_width(0x2FFFD), 2);
+	assert_eq!(ea_width(0x2FFFE), 1);
+	assert_eq!(ea_width(0x110000), 1);
+	// U+231A WATCH is Wide, but Unifont draws it narrow
+	assert_eq!(ea_width(0x231A), 2);
+	assert!(!Unifont::open().is_wide(0x231A));
+    }
+    #[test]
+    fn surrogates() {
+	assert!(CodePoint::try_from(0xDABC).unwrap().is_surrogate());
+	assert!(!CodePoint::from('\u{FFFD}').is_surrogate());
+	let mut unifont = Unifont::open();
+	assert!(!unifont.has_glyph(0xD800));
+	assert_eq!(unifont.get_has_glyph(0xDFFF), Some(false));
+	let replacement = unifont.load_char('\u{FFFD}').to_owned();
+	assert_eq!(unifont.load_bitmap(0xDC00), replacement.as_bitmap());
+	assert!(unifont.load_bitmap_with_status(0xDC00).1);
+	let mut buf = Vec::new();
+	assert!(!unifont.render_to_buf(0xDC00, &mut buf));
+	unifont.set_override(0xDC00, &[0xAA; 16]);
+	assert!(unifont.has_glyph(0xDC00));
+	let sync = SyncUnifont::open();
+	assert_eq!(sync.load_bitmap_exact(0xD800), None);
+	assert_eq!(sync.load_bitmap(0xD800), replacement.as_bitmap());
+    }
+    #[test]
+    #[cfg(feature = "png")]
+    fn png_export() {
+	let mut unifont = Unifont::open();
+	let bitmap = unifont.load_char('今');
+	let png = bitmap.to_png_bytes();
+	assert!(png.starts_with(b"\x89PNG\r\n\x1A\n"));
+	let decoded = image::load_from_memory(&png).unwrap().into_luma8();
+	assert_eq!(decoded, bitmap.to_image());
+    }
+    #[test]
+    #[cfg(feature = "image")]
+    fn rgba_image() {
+	const FG: [u8; 4] = [255, 0, 0, 255];
+	const BG: [u8; 4] = [0, 0, 255, 128];
+	let mut unifont = Unifont::open();
+	for codepoint in ['A' as u32, '今' as u32] {
+	    let bitmap = unifont.load_bitmap(codepoint);
+	    let image = bitmap.to_rgba_image(FG, BG);
+	    assert_eq!(image.dimensions(), bitmap.get_dimensions::<u32>());
+	    for (x, y, pixel) in image.enumerate_pixels() {
+		let expected = if bitmap.get_pixel(x, y) { FG } else { BG };
+		assert_eq!(pixel.0, expected);
+	    }
+	}
+	// the crossbar of 'A'
+	let image = unifont.load_char('A').to_rgba_image(FG, BG);
+	assert_eq!(image.get_pixel(0, 9).0, BG);
+	assert!((1 ..= 6).all(|x| image.get_pixel(x, 9).0 == FG));
+	assert_eq!(image.get_pixel(7, 9).0, BG);
+    }
+    #[test]
+    #[cfg(feature = "embedded-graphics")]
+    fn embedded_graphics() {
+	use embedded_graphics::mock_display::MockDisplay;
+	let mut unifont = Unifont::open();
+	let a = unifont.load_char('A');
+	let origin = Point::new(1, 2);
+	let mut display = MockDisplay::new();
+	display.draw_iter(a.pixels(origin)).unwrap();
+	let pattern = ["         ", "         ",
+		       " ........", " ........", " ........", " ........",
+		       " ...##...", " ..#..#..", " ..#..#..", " .#....#.",
+		       " .#....#.", " .######.", " .#....#.", " .#....#.",
+		       " .#....#.", " .#....#.", " ........", " ........"];
+	display.assert_pattern(&pattern);
+	let mut display = MockDisplay::new();
+	let next = Glyph { bitmap: a, position: origin }.draw(&mut display);
+	assert_eq!(next, Ok(Point::new(9, 2)));
+	display.assert_pattern(&pattern);
+	assert_eq!(Glyph { bitmap: a, position: origin }.bounding_box(),
+		   Rectangle::new(origin, Size::new(8, 16)));
+	let wide = unifont.load_char('井');
+	let mut display = MockDisplay::new();
+	let next = Glyph { bitmap: wide, position: origin }.draw(&mut display);
+	assert_eq!(next, Ok(Point::new(17, 2)));
+	assert_eq!(display.affected_area(),
+		   Rectangle::new(origin, Size::new(16, 16)));
+    }
+    #[test]
+    fn ascii_tables() {
+	let mut unifont = Unifont::open();
+	for c in 0 ..= 255u8 {
+	    let expected = if (0x20 ..= 0x7E).contains(&c) { c as u32 }
+	    else { 0xFFFD };
+	    assert_eq!(ascii_bitmap(c), unifont.load_bitmap(expected).get_bytes());
+	}
+    }
+    #[test]
+    fn ink_bounds() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.load_char('A').ink_bounds(), Some((1, 4, 6, 13)));
+	assert_eq!(unifont.load_char('x').ink_bounds().unwrap().3 as u32,
+		   UNIFONT_BASELINE_ROW);
+	assert_eq!(unifont.load_char('y').ink_bounds().unwrap().3 as u32,
+		   UNIFONT_ASCENT + UNIFONT_DESCENT - 1);
+	assert_eq!(unifont.load_char(' ').ink_bounds(), None);
+	assert!(unifont.load_char(' ').is_blank());
+	assert!(!unifont.load_char('A').is_blank());
+	assert_eq!(unifont.load_char(' ').ink_count(), 0);
+	assert_eq!(unifont.load_char('A').ink_count(), 24);
+	let bitmap = unifont.load_char('今');
+	assert_eq!(bitmap.ink_count(), bitmap.iter_set_pixels().count() as u32);
+	let (_, _, max_x, _) = unifont.load_char('井').ink_bounds().unwrap();
+	assert!(max_x >= 8);
+    }
+    #[test]
+    fn transforms() {
+	let mut unifont = Unifont::open();
+	let open_paren = unifont.load_char('(').to_owned();
+	let close_paren = unifont.load_char(')').to_owned();
+	assert_eq!(open_paren.as_bitmap().flip_horizontal(), close_paren);
+	let wide = unifont.load_char('井').to_owned();
+	let flipped = wide.as_bitmap().flip_horizontal();
+	assert!(flipped.is_wide());
+	assert_eq!((flipped.width(), flipped.height()), (16, 16));
+	assert_eq!(open_paren.as_bitmap().width(), 8);
+	assert_eq!(flipped.as_bitmap().flip_horizontal(), wide);
+	assert_eq!(flipped.as_bitmap().get_pixel(15, 3),
+		   wide.as_bitmap().get_pixel(0, 3));
+	let upside_down = open_paren.as_bitmap().flip_vertical();
+	assert!(!upside_down.is_wide());
+	assert_eq!(upside_down.as_bitmap().get_row(0),
+		   open_paren.as_bitmap().get_row(15));
+	assert_eq!(upside_down.as_bitmap().flip_vertical(), open_paren);
+	let cw = open_paren.as_bitmap().rotate_90_cw();
+	assert!(cw.is_wide());
+	assert!((8 .. 16).all(|y| cw.as_bitmap().get_row(y) == 0));
+	assert_eq!(cw.as_bitmap().get_pixel(15, 0),
+		   open_paren.as_bitmap().get_pixel(0, 0));
+	assert_eq!(cw.as_bitmap().get_pixel(0, 5),
+		   open_paren.as_bitmap().get_pixel(5, 15));
+	assert_eq!(cw.as_bitmap().rotate_90_ccw(),
+		   BitmapBuf::from_rows(true,
+					|y| open_paren.as_bitmap().get_row(y)));
+	let bold = unifont.load_char('|').embolden();
+	assert_eq!(bold.as_bitmap().iter_set_pixels().count(),
+		   unifont.load_char('|').iter_set_pixels().count() * 2);
+	let mut edge = [0u8; 16];
+	edge[0] = 0x01;
+	let edge = BitmapBuf::from_bytes(&edge).unwrap();
+	assert_eq!(edge.as_bitmap().embolden(), edge);
+	let italic = unifont.load_char('|').italicize();
+	assert_eq!(italic.as_bitmap().get_row(0),
+		   unifont.load_char('|').get_row(0) >> 2);
+	assert_eq!(italic.as_bitmap().get_row(15),
+		   unifont.load_char('|').get_row(15));
+	assert_eq!(edge.as_bitmap().italicize().as_bitmap().get_row(0), 0);
+	let negative = unifont.load_char(' ').invert();
+	assert_eq!(negative.get_bytes(), &[0xFF; 16]);
+	assert_eq!(wide.as_bitmap().invert().as_bitmap().invert(), wide);
+	let mut marks = Unifont::open();
+	marks.preload_str("y\u{0301}\u{0308}井");
+	let y = marks.get_char('y').unwrap();
+	let diaeresis = marks.get_bitmap(0x0308).unwrap();
+	let y_diaeresis = y.overlay(&diaeresis);
+	assert!(!y_diaeresis.is_wide());
+	assert_eq!(y_diaeresis.as_bitmap().get_row(3),
+		   y.get_row(3) | diaeresis.get_row(3));
+	assert!(y.overlay(&marks.get_char('井').unwrap()).is_wide());
+	let underlined = y.with_underline();
+	assert_eq!(underlined.as_bitmap().get_row(UNIFONT_UNDERLINE_ROW),
+		   0xFF00);
+	assert_eq!(underlined.as_bitmap().get_row(0), y.get_row(0));
+	assert_eq!(marks.get_char('井').unwrap().with_strikethrough()
+		   .as_bitmap().get_row(UNIFONT_STRIKETHROUGH_ROW), 0xFFFF);
+	let acute = marks.get_bitmap(0x0301).unwrap();
+	assert_eq!(BitmapBuf::stack(&y, &[]), y.to_owned());
+	assert_eq!(BitmapBuf::stack(&y, &[diaeresis,
+					  marks.get_bitmap(0x0301).unwrap()]),
+		   y_diaeresis.as_bitmap().overlay(&acute));
+	assert_eq!(wide.as_bitmap().rotate_90_cw().as_bitmap()
+		   .rotate_90_cw().as_bitmap().rotate_90_cw().as_bitmap()
+		   .rotate_90_cw(), wide);
+    }
+    #[test]
+    fn hash_dedup() {
+	let mut unifont = Unifont::open();
+	unifont.preload_range(0x0000, 0x00FF);
+	let unique: std::collections::HashSet<Bitmap>
+	    = (0x20 ..= 0x7E).chain([0x20, 0x41])
+	    .map(|codepoint| unifont.get_bitmap(codepoint).unwrap())
+	    .collect();
+	assert_eq!(unique.len(), 0x7E - 0x20 + 1);
+	let owned: std::collections::HashSet<BitmapBuf>
+	    = unique.iter().map(Bitmap::to_owned).collect();
+	assert_eq!(owned.len(), unique.len());
+    }
+    #[test]
+    fn atlas() {
+	let mut unifont = Unifont::open();
+	let codepoints = ['A' as u32, '井' as u32, 'B' as u32, 'A' as u32,
+			  0x104560, 0xFFFD];
+	let atlas = unifont.build_atlas(&codepoints);
+	assert_eq!(atlas.width % 16, 0);
+	assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+	assert_eq!(atlas.get(0x104560), atlas.get(0xFFFD));
+	assert_eq!(atlas.get('C' as u32), None);
+	let (x, y, width) = atlas.get('井' as u32).unwrap();
+	assert_eq!(width, 16);
+	let bitmap = unifont.load_char('井');
+	for (bx, by) in (0 .. 16).flat_map(|y| (0 .. 16).map(move |x| (x, y))) {
+	    let pixel = atlas.pixels[((y + by) * atlas.width + x + bx) as usize];
+	    assert_eq!(pixel != 0, bitmap.get_pixel(bx, by));
+	}
+	assert_eq!(unifont.build_atlas(&[]).pixels.len(), 0);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+	let mut unifont = Unifont::open();
+	let bitmap = unifont.load_char('井').to_owned();
+	let json = serde_json::to_string(&bitmap).unwrap();
+	assert_eq!(serde_json::from_str::<BitmapBuf>(&json).unwrap(), bitmap);
+	let bogus = r#"{"wide":true,"bytes":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16]}"#;
+	assert!(serde_json::from_str::<BitmapBuf>(bogus).is_err());
+    }
+    #[test]
+    fn blit_clips() {
+	let mut unifont = Unifont::open();
+	let bitmap = unifont.load_bitmap('井' as u32);
+	let mut dest = vec![0u8; 10 * 12 * 4];
+	bitmap.blit_rgba(&mut dest, 10, 3, 2, [255; 4], Some([1; 4]));
+	for y in 0 .. 12 {
+	    for x in 0 .. 10 {
+		let expected = if x < 3 || y < 2 { 0 }
+		else if bitmap.get_pixel(x - 3, y - 2) { 255 }
+		else { 1 };
+		assert_eq!(dest[((y * 10 + x) * 4) as usize], expected);
+	    }
+	}
+    }
+    #[test]
+    fn hex_lines() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.load_bitmap(0x41).to_hex_line(0x41),
+		   "0041:0000000018242442427E424242420000");
+	#[cfg(not(feature = "bmp-only"))]
+	assert_eq!(unifont.load_bitmap(0x1F600).to_hex_line(0x1F600),
+		   "01F600:000003E00C181004200226324631400140014FF92AAA26B2\
+		    13E40C1803E00000");
+    }
+    #[test]
+    fn downscale() {
+	let full = BitmapBuf::from_bytes(&[0xFF; 16]).unwrap();
+	assert!(full.as_bitmap().downscale_gray(3, 5).iter().all(|&x| x == 255));
+	// left half inked
+	let half = BitmapBuf::from_bytes(&[0xF0; 16]).unwrap();
+	assert_eq!(half.as_bitmap().downscale_gray(2, 1), [255, 0]);
+	assert_eq!(half.as_bitmap().downscale_gray(1, 1), [128]);
+	assert_eq!(half.as_bitmap().downscale_gray(3, 1), [255, 128, 0]);
+	let mut unifont = Unifont::open();
+	let bitmap = unifont.load_char('今');
+	let mut alpha = [0; 256];
+	bitmap.blit_alpha(&mut alpha, 16, 0, 0);
+	assert_eq!(bitmap.downscale_gray(16, 16), alpha);
+	assert_eq!(bitmap.downscale_gray(8, 8).len(), 64);
+    }
+    #[test]
+    fn columns() {
+	let mut unifont = Unifont::open();
+	for c in ['A', '今'] {
+	    let bitmap = unifont.load_char(c);
+	    for x in 0 .. bitmap.width() {
+		for y in 0 .. 16 {
+		    assert_eq!(bitmap.get_column(x) & (0x8000 >> y) != 0,
+			       bitmap.get_pixel(x, y));
+		}
+	    }
+	}
+	let bitmap = unifont.load_char('A');
+	let copy = bitmap;
+	assert_eq!(copy.get_column(0), bitmap.get_column(0));
+	assert!(std::panic::catch_unwind(|| bitmap.get_column(8)).is_err());
+    }
+    #[test]
+    fn visually_eq() {
+	let mut unifont = Unifont::open();
+	let narrow = unifont.load_char('A').to_owned();
+	let mut padded = [0; 32];
+	for (y, &byte) in narrow.get_bytes().iter().enumerate() {
+	    padded[y * 2] = byte;
+	}
+	let wide = BitmapBuf::from_bytes(&padded).unwrap();
+	assert_ne!(narrow.as_bitmap(), wide.as_bitmap());
+	assert!(narrow.as_bitmap().visually_eq(&wide.as_bitmap()));
+	assert!(wide.as_bitmap().visually_eq(&narrow.as_bitmap()));
+	padded[31] = 1;
+	let wide = BitmapBuf::from_bytes(&padded).unwrap();
+	assert!(!narrow.as_bitmap().visually_eq(&wide.as_bitmap()));
+	let b = unifont.load_char('B').to_owned();
+	assert!(!narrow.as_bitmap().visually_eq(&b.as_bitmap()));
+    }
+    #[test]
+    fn padded_bytes() {
+	let mut unifont = Unifont::open();
+	let narrow = unifont.load_char('A');
+	let padded = narrow.get_bytes_padded();
+	for y in 0 .. 16 {
+	    assert_eq!(padded[y * 2 .. y * 2 + 2], [narrow.get_bytes()[y], 0]);
+	}
+	let wide = unifont.load_char('今');
+	assert_eq!(&wide.get_bytes_padded()[..], wide.get_bytes());
+    }
+    #[test]
+    fn xbm_export() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.load_char('A').to_xbm("glyph_A"),
+		   "#define glyph_A_width 8\n\
+		    #define glyph_A_height 16\n\
+		    static unsigned char glyph_A_bits[] = {\n   \
+		    0x00, 0x00, 0x00, 0x00, 0x18, 0x24, 0x24, 0x42, 0x42, 0x7e, \
+		    0x42, 0x42,\n   0x42, 0x42, 0x00, 0x00 };\n");
+	let xbm = unifont.load_char('今').to_xbm("kon");
+	assert!(xbm.starts_with("#define kon_width 16\n"));
+	assert_eq!(xbm.matches("0x").count(), 32);
+	// the leftmost pixel is the least significant bit
+	let xbm = BitmapBuf::from_bytes(&[0x80; 16]).unwrap().as_bitmap()
+	    .to_xbm("left");
+	assert_eq!(xbm.matches("0x01").count(), 16);
+    }
+    #[test]
+    fn bmp_export() {
+	let mut unifont = Unifont::open();
+	for c in ['A', '今'] {
+	    let bitmap = unifont.load_char(c);
+	    let bmp = bitmap.to_bmp();
+	    assert_eq!(bmp.len(), 62 + 64);
+	    assert_eq!(&bmp[..2], b"BM");
+	    assert_eq!(bmp[2..6], (bmp.len() as u32).to_le_bytes());
+	    assert_eq!(bmp[18..22], bitmap.width().to_le_bytes());
+	    let row_bytes = bitmap.width() as usize / 8;
+	    for y in 0 .. 16 {
+		let row = &bmp[62 + (15 - y) * 4 ..][.. 4];
+		assert_eq!(&row[.. row_bytes],
+			   &bitmap.get_bytes()[y * row_bytes ..][.. row_bytes]);
+		assert!(row[row_bytes ..].iter().all(|&x| x == 0));
+	    }
+	}
+    }
+    #[test]
+    fn glyph_presence() {
+	let mut unifont = Unifont::open();
+	assert_eq!(unifont.get_has_glyph('A' as u32), None);
+	assert!(unifont.has_glyph('A' as u32));
+	assert_eq!(unifont.get_has_glyph('A' as u32), Some(true));
+	assert!(unifont.is_page_loaded(0));
+	unifont.purge_page(0);
+	assert!(!unifont.is_page_loaded(0));
+	unifont.load_page(0x4E);
+	unifont.clear();
+	assert_eq!(unifont.loaded_pages().count(), 0);
+	assert_eq!(unifont.memory_usage(), 0);
+	assert_eq!(unifont.get_has_glyph('A' as u32), None);
+	assert!(!unifont.has_glyph(0x104560));
+	assert!(unifont.load_bitmap_exact(0x104560).is_none());
+	assert!(unifont.load_bitmap_exact('A' as u32).is_some());
+	assert_eq!(unifont.glyph_width('A' as u32), 8);
+	assert_eq!(unifont.glyph_width('井' as u32), 16);
+	assert_eq!(unifont.glyph_width(0x104560), 8);
+	let mut clone = unifont.clone();
+	assert!(!clone.is_page_loaded(0));
+	assert!(clone.has_glyph('A' as u32));
+    }
+    #[test]
+    fn every_page_loads() {
+	let mut unifont = Unifont::try_open().unwrap();
+	for page in 0 ..= MAX_UNICODE_PAGE {
+	    assert_eq!(unifont.try_load_page(page), Ok(()));
+	}
+    }
+    #[test]
+    fn preloaded_str() {
+	let mut unifont = Unifont::open();
+	let s = "Hello, 世界! \u{104560}";
+	unifont.preload_str(s);
+	for c in s.chars() {
+	    assert!(unifont.get_char(c).is_some());
+	}
+    }
+    #[test]
+    fn frozen_is_shareable() {
+	fn assert_send_sync<T: Send + Sync>(_: &T) {}
+	let frozen = Unifont::open().into_frozen();
+	assert_send_sync(&frozen);
+	assert_eq!(frozen.get_bitmap(0x104560), frozen.get_bitmap(0xFFFD));
+	assert!(frozen.get_char('井').is_wide());
+	let owned = Unifont::open().load_char('井').to_owned();
+	assert_send_sync(&owned);
+	assert_eq!(owned.as_bitmap(), frozen.get_char('井'));
+    }
+    #[test]
+    fn sync_loads_across_threads() {
+	let unifont = SyncUnifont::open();
+	std::thread::scope(|scope| {
+	    for c in ['A', '井', '\u{104560}'] {
+		let unifont = &unifont;
+		scope.spawn(move || {
+		    let mut reference = Unifont::open();
+		    assert_eq!(unifont.load_char(c), reference.load_char(c));
+		});
+	    }
+	});
+    }
 }